@@ -1,53 +1,380 @@
-use std::time::Duration;
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
 
 use anyhow::Result;
 use crossterm::event::{self, Event, KeyCode, KeyEventKind};
 use kube::Client;
 use ratatui::DefaultTerminal;
 use ratatui::widgets::TableState;
+use tokio::sync::{mpsc, watch};
+use tokio::task::JoinHandle;
+use tokio::time::timeout;
 
-use crate::k8s::{self, DeploymentInfo, PodInfo};
+use crate::cluster::Cluster;
+use crate::config::ConfigFile;
+use crate::history::{self, MetricsHistory, Series};
+use crate::k8s::metrics::Usage;
+use crate::k8s::rollout::{self, DiffLine, Revision, RolloutStatus};
+use crate::k8s::{
+    self, DeploymentInfo, NodeInfo, PodInfo, ResourceKind, ResourceRef, WorkloadKind,
+};
+use crate::ssh::ConnectionState;
+use crate::ssh::native::UnknownHostKey;
 use crate::ui;
 
 const POLL_INTERVAL: Duration = Duration::from_secs(5);
+/// Longest wait for one cluster of the all-clusters view to list.
+const FETCH_TIMEOUT: Duration = Duration::from_secs(15);
 const TICK_RATE: Duration = Duration::from_millis(200);
+const SCALE_TIMEOUT: Duration = Duration::from_secs(180);
+const LOG_TAIL_LINES: i64 = 100;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Panel {
     Pods,
     Deployments,
+    Nodes,
+}
+
+/// Modal dialog drawn on top of the dashboard.
+pub enum Dialog {
+    Scale(ScaleDialog),
+    Rollout(RolloutView),
+    Delete(DeleteDialog),
+    Label(LabelDialog),
+    Logs(LogView),
+    Results(ResultsView),
+    Detail(DetailView),
+    Clusters(ClusterPicker),
+    Contexts(ContextPicker),
+    HostKey(HostKeyDialog),
+}
+
+pub struct ScaleDialog {
+    pub kind: WorkloadKind,
+    pub namespace: String,
+    pub name: String,
+    pub current: i32,
+    pub input: String,
+}
+
+/// Confirmation for deleting one or more resources.
+pub struct DeleteDialog {
+    pub targets: Vec<ResourceRef>,
+    /// Grace period in seconds; empty keeps the resource's default.
+    pub grace: String,
+    pub force: bool,
+}
+
+/// Whether to trust the unknown host key a switch failed on, and connect
+/// again if so.
+pub struct HostKeyDialog {
+    pub key: UnknownHostKey,
+    retry: Switch,
+}
+
+/// Input for `key=value` (set) or `key-` (remove) on one or more resources.
+pub struct LabelDialog {
+    pub targets: Vec<ResourceRef>,
+    pub input: String,
+}
+
+/// Tail of the logs of one or more pods, reloaded on every refresh.
+pub struct LogView {
+    pub pods: Vec<ResourceRef>,
+    /// Containers of each pod, default one first; loaded with the first tail.
+    pub containers: Vec<Vec<String>>,
+    /// Container shown when tailing a single pod, an index into its containers.
+    pub container: usize,
+    pub lines: Vec<String>,
+    /// Lines scrolled up from the newest one; 0 follows the tail.
+    pub scroll_back: u16,
+}
+
+/// Usage history of a single pod or node, updated on every refresh.
+pub enum DetailView {
+    Pod { pod: PodInfo, series: Series },
+    Node { node: NodeInfo, series: Series },
+}
+
+/// Per-resource outcome of a bulk action.
+pub struct ResultsView {
+    pub title: String,
+    pub results: Vec<(ResourceRef, Result<(), String>)>,
+    /// Results scrolled past at the top.
+    pub scroll: u16,
+}
+
+/// Live rollout status and revision history of a deployment.
+pub struct RolloutView {
+    pub namespace: String,
+    pub name: String,
+    pub status: Option<RolloutStatus>,
+    pub history: Vec<Revision>,
+    pub history_state: TableState,
+    /// Revision picked as the base of the next diff.
+    pub marked: Option<i64>,
+    pub diff: Option<RevisionDiff>,
+}
+
+/// What the dashboard shows: one cluster profile or all of them at once.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Scope {
+    Cluster(String),
+    All,
+}
+
+impl std::fmt::Display for Scope {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Scope::Cluster(name) => write!(f, "{}", name),
+            Scope::All => write!(f, "all clusters"),
+        }
+    }
+}
+
+/// List of the configured cluster profiles to switch to.
+pub struct ClusterPicker {
+    pub entries: Vec<Scope>,
+    pub current: Option<Scope>,
+    pub state: TableState,
+}
+
+/// List of the kubeconfig contexts of the shown cluster.
+pub struct ContextPicker {
+    pub names: Vec<String>,
+    pub current: Option<String>,
+    pub state: TableState,
+}
+
+/// Reconnect picked in a picker, carried out by the run loop.
+struct Switch {
+    /// `None` for a config without `[clusters]`.
+    scope: Option<Scope>,
+    /// Context picked in the context picker, `None` keeps the scope's own.
+    context: Option<String>,
+}
+
+/// Connections for another scope being set up in the background.
+pub struct Switching {
+    pub scope: Option<Scope>,
+    /// Scope shown before, back on screen if no cluster comes up.
+    previous: Option<Scope>,
+    pub context: Option<String>,
+    pub connects: Vec<Connect>,
+    pub started: Instant,
+    /// Limit the tables to the namespace of the new context once connected.
+    reset_namespace: bool,
+    /// Nothing was shown before, this is the connect at startup.
+    first: bool,
+}
+
+impl Switching {
+    /// What is being connected to, for messages and the progress overlay.
+    pub fn target(&self) -> String {
+        match (&self.scope, &self.context) {
+            (Some(scope), Some(context)) => format!("{} ({})", scope, context),
+            (Some(scope), None) => scope.to_string(),
+            (None, Some(context)) => context.clone(),
+            (None, None) => "cluster".into(),
+        }
+    }
+}
+
+/// One cluster being connected to.
+pub struct Connect {
+    pub name: String,
+    pub step: watch::Receiver<Step>,
+    task: JoinHandle<Result<Cluster>>,
+}
+
+/// Progress of a [`Connect`].
+#[derive(Clone)]
+pub enum Step {
+    /// Latest step reported by [`Cluster::connect`].
+    Running(String),
+    Done,
+    Failed(String),
+}
+
+/// Dashboard state kept per cluster while another one is shown.
+struct ClusterView {
+    active_panel: Panel,
+    filter: String,
+    namespace: Option<String>,
+    /// Context in use, connected to again when switching back.
+    context: Option<String>,
+    pods_state: TableState,
+    deployments_state: TableState,
+    nodes_state: TableState,
+}
+
+pub struct RevisionDiff {
+    pub from: i64,
+    pub to: i64,
+    pub lines: Vec<DiffLine>,
+    pub scroll: u16,
+}
+
+/// Work queued by key handlers that needs the k8s client.
+enum Action {
+    Scale {
+        kind: WorkloadKind,
+        namespace: String,
+        name: String,
+        replicas: i32,
+    },
+    Restart {
+        targets: Vec<ResourceRef>,
+    },
+    Undo {
+        namespace: String,
+        name: String,
+        revision: i64,
+    },
+    LoadRollout,
+    Delete {
+        targets: Vec<ResourceRef>,
+        grace_period: Option<u32>,
+        force: bool,
+    },
+    Label {
+        targets: Vec<ResourceRef>,
+        key: String,
+        value: Option<String>,
+    },
+    LoadLogs,
+}
+
+/// A scaled workload whose READY count is followed until it converges.
+pub struct ScaleTracker {
+    pub kind: WorkloadKind,
+    pub namespace: String,
+    pub name: String,
+    pub target: i32,
+    started: Instant,
 }
 
 pub struct App {
     pub active_panel: Panel,
     pub pods: Vec<PodInfo>,
     pub deployments: Vec<DeploymentInfo>,
+    pub nodes: Vec<NodeInfo>,
     pub pods_state: TableState,
     pub deployments_state: TableState,
+    pub nodes_state: TableState,
+    /// Whether metrics.k8s.io answered on the last poll, `None` before the first.
+    pub metrics_available: Option<bool>,
+    /// Whether nodes could be listed; users bound to a namespace may not.
+    pub nodes_available: bool,
+    pub history: MetricsHistory,
+    last_sample: Option<Instant>,
+    pub dialog: Option<Dialog>,
+    pub scaling: Vec<ScaleTracker>,
+    /// Deleted resources still present in the last listing.
+    pub terminating: Vec<ResourceRef>,
+    /// Rows marked for bulk actions.
+    pub selection: HashSet<ResourceRef>,
+    /// Case-insensitive substring matched against name and namespace.
+    pub filter: String,
+    /// Namespace pods and workloads are limited to, from the kubeconfig context.
+    pub namespace: Option<String>,
+    pub filter_editing: bool,
+    pub message: Option<String>,
     pub error: Option<String>,
     pub should_quit: bool,
+    /// State of the SSH tunnel; API calls pause while it is down.
+    pub connection: ConnectionState,
+    /// What is shown, `None` for a config without `[clusters]`.
+    pub scope: Option<Scope>,
+    /// All configured profiles, in file order.
+    pub clusters: Vec<String>,
+    /// Clusters of the all-clusters view that could not be listed, with why.
+    pub cluster_errors: Vec<(String, String)>,
+    /// Clusters of the all-clusters view that listed only in part, with why.
+    pub cluster_warnings: Vec<(String, String)>,
+    /// Clusters of the all-clusters view that failed to connect.
+    connect_errors: Vec<(String, String)>,
+    /// Contexts in the kubeconfig of the shown cluster.
+    pub contexts: Vec<String>,
+    /// Context of the shown cluster, `None` when the kubeconfig names none.
+    pub context: Option<String>,
+    pub switching: Option<Switching>,
+    switch_to: Option<Switch>,
+    views: HashMap<Option<Scope>, ClusterView>,
+    /// Only list pods, workloads and nodes that are not healthy.
+    pub problems_only: bool,
+    pending: Vec<Action>,
+    /// Last listing of each cluster in the all-clusters view, by name.
+    listings: HashMap<String, Listing>,
+    /// Why the last listing of a cluster failed, by name.
+    listing_errors: HashMap<String, String>,
+    /// Clusters with a listing on the way.
+    fetching: HashSet<String>,
+    /// Counts scope changes, so listings started before one are dropped.
+    generation: u64,
+    listings_tx: mpsc::UnboundedSender<(u64, String, Result<Listing, String>)>,
+    listings_rx: mpsc::UnboundedReceiver<(u64, String, Result<Listing, String>)>,
 }
 
 impl App {
     pub fn new() -> Self {
         let mut pods_state = TableState::default();
         pods_state.select(Some(0));
+        let (listings_tx, listings_rx) = mpsc::unbounded_channel();
 
         Self {
             active_panel: Panel::Pods,
             pods: Vec::new(),
             deployments: Vec::new(),
+            nodes: Vec::new(),
             pods_state,
             deployments_state: TableState::default(),
+            nodes_state: TableState::default(),
+            metrics_available: None,
+            nodes_available: true,
+            history: MetricsHistory::default(),
+            last_sample: None,
+            dialog: None,
+            scaling: Vec::new(),
+            terminating: Vec::new(),
+            selection: HashSet::new(),
+            filter: String::new(),
+            namespace: None,
+            filter_editing: false,
+            message: None,
             error: None,
             should_quit: false,
+            connection: ConnectionState::Connected,
+            scope: None,
+            clusters: Vec::new(),
+            cluster_errors: Vec::new(),
+            cluster_warnings: Vec::new(),
+            connect_errors: Vec::new(),
+            contexts: Vec::new(),
+            context: None,
+            switching: None,
+            switch_to: None,
+            views: HashMap::new(),
+            problems_only: false,
+            pending: Vec::new(),
+            listings: HashMap::new(),
+            listing_errors: HashMap::new(),
+            fetching: HashSet::new(),
+            generation: 0,
+            listings_tx,
+            listings_rx,
         }
     }
 
-    /// Main loop: poll K8s data and handle input.
-    pub async fn run(&mut self, terminal: &mut DefaultTerminal, client: &Client) -> Result<()> {
-        // initial data fetch
-        self.refresh(client).await;
+    /// Main loop: poll K8s data and handle input. The first cluster is
+    /// connected to by a switch started with [`App::switch`], other profiles
+    /// from `config` can be switched to from the cluster picker.
+    pub async fn run(&mut self, terminal: &mut DefaultTerminal, config: &ConfigFile) -> Result<()> {
+        self.clusters = config.cluster_names();
+        let mut connection = watch::channel(ConnectionState::Connecting).1;
+        // one cluster, or every cluster that connected in the all-clusters view
+        let mut connected: Vec<Cluster> = Vec::new();
 
         let mut last_poll = std::time::Instant::now();
 
@@ -55,18 +382,172 @@ impl App {
             terminal.draw(|f| ui::draw(f, self))?;
 
             // small timeout to handle input events
-            if event::poll(TICK_RATE)? {
-                if let Event::Key(key) = event::read()? {
-                    if key.kind == KeyEventKind::Press {
-                        self.handle_key(key.code);
-                    }
-                }
+            if event::poll(TICK_RATE)?
+                && let Event::Key(key) = event::read()?
+                && key.kind == KeyEventKind::Press
+            {
+                self.handle_key(key.code);
             }
 
             if self.should_quit {
                 break;
             }
 
+            // connect to the picked clusters, the current ones stay up until then
+            if let Some(switch) = self.switch_to.take() {
+                self.start_switch(config, switch);
+            }
+            if self.switching.as_ref().is_some_and(|switching| {
+                switching
+                    .connects
+                    .iter()
+                    .all(|connect| connect.task.is_finished())
+            }) && let Some(switching) = self.switching.take()
+            {
+                let target = switching.target();
+                let mut fresh = Vec::new();
+                let mut errors = Vec::new();
+                let mut host_key = None;
+                for connect in switching.connects {
+                    match connect.task.await {
+                        Ok(Ok(cluster)) => fresh.push(cluster),
+                        Ok(Err(e)) => {
+                            errors.push((connect.name, format!("{:#}", e)));
+                            if host_key.is_none() {
+                                host_key = e.downcast::<UnknownHostKey>().ok();
+                            }
+                        }
+                        Err(e) => errors.push((connect.name, e.to_string())),
+                    }
+                }
+
+                if fresh.is_empty() && !connected.is_empty() {
+                    // nothing came up, stay on the clusters still connected
+                    self.show_view(&switching.previous);
+                    let error = errors.pop().map(|(_, e)| e).unwrap_or_default();
+                    match &self.scope {
+                        Some(Scope::All) => {
+                            self.connection = ConnectionState::Connected;
+                            self.fetch_all(&connected);
+                            self.show_listings(&connected);
+                        }
+                        _ => {
+                            let cluster = &connected[0];
+                            self.use_contexts(cluster, false);
+                            self.connection = connection.borrow_and_update().clone();
+                            self.refresh(&cluster.client).await;
+                        }
+                    }
+                    last_poll = std::time::Instant::now();
+                    self.switch_failed(&target, error, switching.context.is_some());
+                } else {
+                    for cluster in connected.drain(..) {
+                        cluster.close().await;
+                    }
+                    connected = fresh;
+                    match switching.scope {
+                        Some(Scope::All) => {
+                            self.connection = ConnectionState::Connected;
+                            self.message = Some(format!(
+                                "Connected to {} of {} clusters",
+                                connected.len(),
+                                connected.len() + errors.len()
+                            ));
+                            self.connect_errors = errors;
+                            self.fetch_all(&connected);
+                            self.show_listings(&connected);
+                            last_poll = std::time::Instant::now();
+                        }
+                        _ => match connected.first() {
+                            Some(cluster) => {
+                                connection = cluster.state();
+                                self.connection = connection.borrow_and_update().clone();
+                                self.use_contexts(cluster, switching.reset_namespace);
+                                let verb = if switching.first {
+                                    "Connected to"
+                                } else {
+                                    "Switched to"
+                                };
+                                self.message = Some(format!("{} {}", verb, target));
+                                self.refresh(&cluster.client).await;
+                                last_poll = std::time::Instant::now();
+                            }
+                            None => {
+                                // the old tunnel is gone, don't follow it anymore
+                                connection = watch::channel(ConnectionState::Connecting).1;
+                                let error = errors.pop().map(|(_, e)| e).unwrap_or_default();
+                                self.connection = ConnectionState::Failed(error.clone());
+                                self.switch_failed(&target, error, switching.context.is_some());
+                            }
+                        },
+                    }
+                }
+                // no terminal to confirm an unknown host key on, ask here instead
+                if let Some(key) = host_key {
+                    self.dialog = Some(Dialog::HostKey(HostKeyDialog {
+                        key,
+                        retry: Switch {
+                            scope: switching.scope,
+                            context: switching.context,
+                        },
+                    }));
+                }
+            }
+            if self.switching.is_some() {
+                continue;
+            }
+
+            // every cluster lists on its own, failures only show up as errors
+            if self.scope == Some(Scope::All) {
+                if !self.pending.is_empty() {
+                    self.pending.clear();
+                    self.dialog = None;
+                    self.message =
+                        Some("Pick a single cluster with c to act on its resources".into());
+                }
+                self.take_listings(&connected);
+                if last_poll.elapsed() >= POLL_INTERVAL {
+                    self.fetch_all(&connected);
+                    self.show_listings(&connected);
+                    last_poll = std::time::Instant::now();
+                }
+                continue;
+            }
+            let Some(client) = connected.first().map(|cluster| &cluster.client) else {
+                if !self.pending.is_empty() {
+                    self.pending.clear();
+                    self.message = Some("Not connected to a cluster".into());
+                }
+                continue;
+            };
+
+            // follow the tunnel, polling resumes once it is back
+            if connection.has_changed().unwrap_or(false) {
+                let state = connection.borrow_and_update().clone();
+                let resumed = state == ConnectionState::Connected
+                    && self.connection != ConnectionState::Connected;
+                self.connection = state;
+                if resumed {
+                    self.message = Some("Reconnected".into());
+                    self.refresh(client).await;
+                    last_poll = std::time::Instant::now();
+                }
+            }
+            if self.connection != ConnectionState::Connected {
+                if !self.pending.is_empty() {
+                    self.pending.clear();
+                    self.message = Some("Not connected, try again once the tunnel is back".into());
+                }
+                continue;
+            }
+
+            // apply queued actions, then show their effect right away
+            if !self.pending.is_empty() {
+                self.run_actions(client).await;
+                self.refresh(client).await;
+                last_poll = std::time::Instant::now();
+            }
+
             // refresh
             if last_poll.elapsed() >= POLL_INTERVAL {
                 self.refresh(client).await;
@@ -74,41 +555,883 @@ impl App {
             }
         }
 
+        if let Some(switching) = self.switching.take() {
+            for connect in switching.connects {
+                connect.task.abort();
+            }
+        }
+        for cluster in connected {
+            cluster.close().await;
+        }
         Ok(())
     }
 
+    /// Connect to `scope` once the run loop starts, `None` for a config
+    /// without `[clusters]`. A `context` replaces the one of the profile.
+    pub fn switch(&mut self, scope: Option<Scope>, context: Option<String>) {
+        self.switch_to = Some(Switch { scope, context });
+    }
+
+    /// Keep the view of the current scope, clear everything loaded from it
+    /// and connect to the clusters of the new scope in the background.
+    fn start_switch(&mut self, config: &ConfigFile, switch: Switch) {
+        let Switch { scope, context } = switch;
+        // another context of the same cluster can still be picked if this one fails
+        if scope != self.scope {
+            self.contexts.clear();
+        }
+        let previous = self.scope.clone();
+        let first = self.views.is_empty() && self.scope.is_none();
+        // a view seen before keeps its namespace, unless the context changes
+        let (seen, view_context) = self.show_view(&scope);
+        let reset_namespace = first || !seen || context.is_some();
+        let context = context.or(view_context);
+
+        let names = match &scope {
+            Some(Scope::Cluster(name)) => vec![Some(name.clone())],
+            Some(Scope::All) => self.clusters.iter().cloned().map(Some).collect(),
+            None => vec![None],
+        };
+        let connects = names
+            .into_iter()
+            .map(|name| {
+                let cfg = config.cluster(name.as_deref()).map(|mut cfg| {
+                    if context.is_some() {
+                        cfg.kubernetes.context = context.clone();
+                    }
+                    cfg
+                });
+                let (tx, step) = watch::channel(Step::Running(String::new()));
+                let task = tokio::spawn(async move {
+                    // no terminal to confirm host keys on while the dashboard runs
+                    let result = match cfg {
+                        Ok(cfg) => {
+                            let progress = |s| {
+                                tx.send_replace(Step::Running(s));
+                            };
+                            Cluster::connect(&cfg, false, progress).await
+                        }
+                        Err(e) => Err(e),
+                    };
+                    tx.send_replace(match &result {
+                        Ok(_) => Step::Done,
+                        Err(e) => Step::Failed(format!("{:#}", e)),
+                    });
+                    result
+                });
+                let name = name.unwrap_or_else(|| "cluster".into());
+                Connect { name, step, task }
+            })
+            .collect();
+
+        self.switching = Some(Switching {
+            scope,
+            previous,
+            context,
+            connects,
+            started: Instant::now(),
+            reset_namespace,
+            first,
+        });
+    }
+
+    /// Keep the view of the current scope and show the one of `scope`, with
+    /// nothing loaded yet. Returns whether `scope` was shown before and the
+    /// context it used.
+    fn show_view(&mut self, scope: &Option<Scope>) -> (bool, Option<String>) {
+        self.views.insert(
+            self.scope.take(),
+            ClusterView {
+                active_panel: self.active_panel,
+                filter: std::mem::take(&mut self.filter),
+                namespace: self.namespace.take(),
+                context: self.context.take(),
+                pods_state: std::mem::take(&mut self.pods_state),
+                deployments_state: std::mem::take(&mut self.deployments_state),
+                nodes_state: std::mem::take(&mut self.nodes_state),
+            },
+        );
+
+        let view = self.views.remove(scope);
+        let seen = view.is_some();
+        let view = view.unwrap_or_else(|| ClusterView {
+            active_panel: Panel::Pods,
+            filter: String::new(),
+            namespace: None,
+            context: None,
+            pods_state: TableState::default().with_selected(0),
+            deployments_state: TableState::default(),
+            nodes_state: TableState::default(),
+        });
+        self.scope = scope.clone();
+        self.active_panel = view.active_panel;
+        self.filter = view.filter;
+        self.namespace = view.namespace;
+        self.pods_state = view.pods_state;
+        self.deployments_state = view.deployments_state;
+        self.nodes_state = view.nodes_state;
+
+        self.pods.clear();
+        self.deployments.clear();
+        self.nodes.clear();
+        self.metrics_available = None;
+        self.nodes_available = true;
+        self.history.clear();
+        self.last_sample = None;
+        self.dialog = None;
+        self.scaling.clear();
+        self.terminating.clear();
+        self.selection.clear();
+        self.filter_editing = false;
+        self.message = None;
+        self.error = None;
+        self.cluster_errors.clear();
+        self.cluster_warnings.clear();
+        self.connect_errors.clear();
+        self.listings.clear();
+        self.listing_errors.clear();
+        self.fetching.clear();
+        self.generation += 1;
+        self.connection = ConnectionState::Connecting;
+        (seen, view.context)
+    }
+
+    /// Show why connecting failed and offer the picker it was started from
+    /// again.
+    fn switch_failed(&mut self, target: &str, error: String, context: bool) {
+        self.error = Some(format!("{}: {}", target, error));
+        if context {
+            self.open_context_picker();
+        } else {
+            self.open_cluster_picker();
+        }
+    }
+
+    /// Take the contexts of the shown `cluster`; with `reset_namespace`, also
+    /// limit the tables to the namespace of its context.
+    fn use_contexts(&mut self, cluster: &Cluster, reset_namespace: bool) {
+        self.contexts = cluster.contexts.clone();
+        self.context = cluster.context.clone();
+        if reset_namespace {
+            self.namespace = cluster.namespace.clone();
+            self.set_filter(self.filter.clone());
+        }
+    }
+
     /// Demo run loop
     pub async fn run_demo(&mut self, terminal: &mut DefaultTerminal) -> Result<()> {
         loop {
             terminal.draw(|f| ui::draw(f, self))?;
 
-            if event::poll(TICK_RATE)? {
-                if let Event::Key(key) = event::read()? {
-                    if key.kind == KeyEventKind::Press {
-                        self.handle_key(key.code);
-                    }
-                }
+            if event::poll(TICK_RATE)?
+                && let Event::Key(key) = event::read()?
+                && key.kind == KeyEventKind::Press
+            {
+                self.handle_key(key.code);
             }
 
             if self.should_quit {
                 break;
             }
+
+            // no cluster to talk to
+            if !self.pending.is_empty() {
+                self.pending.clear();
+                self.dialog = None;
+                self.message = Some("Not available in demo mode".into());
+            }
         }
 
         Ok(())
     }
 
     fn handle_key(&mut self, key: KeyCode) {
+        // only quitting is possible while another cluster is connecting
+        if self.switching.is_some() {
+            if key == KeyCode::Char('q') {
+                self.should_quit = true;
+            }
+            return;
+        }
+        if self.dialog.is_some() {
+            self.handle_dialog_key(key);
+            return;
+        }
+        if self.filter_editing {
+            self.handle_filter_key(key);
+            return;
+        }
+
         match key {
+            KeyCode::Esc if !self.selection.is_empty() => self.selection.clear(),
+            KeyCode::Esc if !self.filter.is_empty() => self.set_filter(String::new()),
+            KeyCode::Esc if self.namespace.is_some() => {
+                self.namespace = None;
+                self.set_filter(self.filter.clone());
+            }
             KeyCode::Char('q') | KeyCode::Esc => self.should_quit = true,
             KeyCode::Tab => self.toggle_panel(),
             KeyCode::Down | KeyCode::Char('j') => self.scroll_down(),
             KeyCode::Up | KeyCode::Char('k') => self.scroll_up(),
+            KeyCode::Char('/') => self.filter_editing = true,
+            KeyCode::Char(' ') => self.toggle_mark(),
+            KeyCode::Char('*') => self.mark_all(),
+            KeyCode::Char('s') => self.open_scale_dialog(),
+            KeyCode::Char('R') => self.restart_selected(),
+            KeyCode::Char('o') => self.open_rollout_view(),
+            KeyCode::Char('d') | KeyCode::Delete => self.open_delete_dialog(),
+            KeyCode::Char('L') => self.open_label_dialog(),
+            KeyCode::Char('l') => self.open_log_view(),
+            KeyCode::Enter => self.open_detail_view(),
+            KeyCode::Char('c') => self.open_cluster_picker(),
+            KeyCode::Char('x') => self.open_context_picker(),
+            KeyCode::Char('!') => self.toggle_problems(),
             KeyCode::Char('r') => { /* handled in run loop via flag */ }
             _ => {}
         }
     }
 
+    fn handle_dialog_key(&mut self, key: KeyCode) {
+        match self.dialog {
+            Some(Dialog::Scale(_)) => self.handle_scale_key(key),
+            Some(Dialog::Rollout(_)) => self.handle_rollout_key(key),
+            Some(Dialog::Delete(_)) => self.handle_delete_key(key),
+            Some(Dialog::Label(_)) => self.handle_label_key(key),
+            Some(Dialog::Logs(_)) => self.handle_logs_key(key),
+            Some(Dialog::Clusters(_)) => self.handle_cluster_key(key),
+            Some(Dialog::Contexts(_)) => self.handle_context_key(key),
+            Some(Dialog::Detail(_)) => {
+                if matches!(key, KeyCode::Esc | KeyCode::Enter | KeyCode::Char('q')) {
+                    self.dialog = None;
+                }
+            }
+            Some(Dialog::Results(_)) => self.handle_results_key(key),
+            Some(Dialog::HostKey(_)) => self.handle_host_key_key(key),
+            None => {}
+        }
+    }
+
+    fn handle_filter_key(&mut self, key: KeyCode) {
+        let mut filter = self.filter.clone();
+        match key {
+            KeyCode::Enter => self.filter_editing = false,
+            KeyCode::Esc => {
+                self.filter_editing = false;
+                filter.clear();
+            }
+            KeyCode::Backspace => {
+                filter.pop();
+            }
+            KeyCode::Char(c) => filter.push(c),
+            _ => {}
+        }
+        if filter != self.filter {
+            self.set_filter(filter);
+        }
+    }
+
+    fn toggle_problems(&mut self) {
+        self.problems_only = !self.problems_only;
+        self.set_filter(self.filter.clone());
+    }
+
+    fn set_filter(&mut self, filter: String) {
+        self.filter = filter;
+        // indices refer to the filtered rows, start over at the top
+        let pods = self.visible_pods().len();
+        let deployments = self.visible_deployments().len();
+        let nodes = self.visible_nodes().len();
+        self.pods_state.select((pods > 0).then_some(0));
+        self.deployments_state
+            .select((deployments > 0).then_some(0));
+        self.nodes_state.select((nodes > 0).then_some(0));
+    }
+
+    fn handle_label_key(&mut self, key: KeyCode) {
+        let Some(Dialog::Label(dialog)) = &mut self.dialog else {
+            return;
+        };
+
+        match key {
+            KeyCode::Esc => self.dialog = None,
+            KeyCode::Backspace => {
+                dialog.input.pop();
+            }
+            KeyCode::Char(c) => dialog.input.push(c),
+            KeyCode::Enter => {
+                let input = dialog.input.trim();
+                let (key, value) = match input.split_once('=') {
+                    Some((k, v)) => (k, Some(v.to_string())),
+                    None => match input.strip_suffix('-') {
+                        Some(k) => (k, None),
+                        None => return,
+                    },
+                };
+                if key.is_empty() {
+                    return;
+                }
+                self.pending.push(Action::Label {
+                    targets: dialog.targets.clone(),
+                    key: key.to_string(),
+                    value,
+                });
+                self.dialog = None;
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_logs_key(&mut self, key: KeyCode) {
+        let Some(Dialog::Logs(view)) = &mut self.dialog else {
+            return;
+        };
+
+        match key {
+            KeyCode::Esc | KeyCode::Char('q') => self.dialog = None,
+            KeyCode::Down | KeyCode::Char('j') => {
+                view.scroll_back = view.scroll_back.saturating_sub(1)
+            }
+            KeyCode::Up | KeyCode::Char('k') => {
+                let max = view.lines.len().saturating_sub(1) as u16;
+                view.scroll_back = (view.scroll_back + 1).min(max);
+            }
+            KeyCode::Char('f') | KeyCode::Char('G') => view.scroll_back = 0,
+            KeyCode::Char('c') => {
+                let count = match view.containers.as_slice() {
+                    [containers] => containers.len(),
+                    _ => 0,
+                };
+                if count > 1 {
+                    view.container = (view.container + 1) % count;
+                    view.lines.clear();
+                    view.scroll_back = 0;
+                    self.pending.push(Action::LoadLogs);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_results_key(&mut self, key: KeyCode) {
+        let Some(Dialog::Results(view)) = &mut self.dialog else {
+            return;
+        };
+
+        match key {
+            KeyCode::Esc | KeyCode::Enter | KeyCode::Char('q') => self.dialog = None,
+            KeyCode::Down | KeyCode::Char('j') => {
+                let max = view.results.len().saturating_sub(1) as u16;
+                view.scroll = (view.scroll + 1).min(max);
+            }
+            KeyCode::Up | KeyCode::Char('k') => view.scroll = view.scroll.saturating_sub(1),
+            _ => {}
+        }
+    }
+
+    fn handle_cluster_key(&mut self, key: KeyCode) {
+        let Some(Dialog::Clusters(picker)) = &mut self.dialog else {
+            return;
+        };
+        let len = picker.entries.len();
+        match key {
+            KeyCode::Esc | KeyCode::Char('q') => self.dialog = None,
+            KeyCode::Down | KeyCode::Char('j') => scroll(&mut picker.state, len, 1),
+            KeyCode::Up | KeyCode::Char('k') => scroll(&mut picker.state, len, -1),
+            KeyCode::Enter => {
+                let picked = picker
+                    .state
+                    .selected()
+                    .and_then(|i| picker.entries.get(i))
+                    .cloned();
+                // picking what is shown just closes the picker, unless it failed
+                let failed = matches!(self.connection, ConnectionState::Failed(_));
+                if picked.is_some() && (picked != self.scope || failed) {
+                    self.switch_to = Some(Switch {
+                        scope: picked,
+                        context: None,
+                    });
+                }
+                self.dialog = None;
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_context_key(&mut self, key: KeyCode) {
+        let Some(Dialog::Contexts(picker)) = &mut self.dialog else {
+            return;
+        };
+        let len = picker.names.len();
+        match key {
+            KeyCode::Esc | KeyCode::Char('q') => self.dialog = None,
+            KeyCode::Down | KeyCode::Char('j') => scroll(&mut picker.state, len, 1),
+            KeyCode::Up | KeyCode::Char('k') => scroll(&mut picker.state, len, -1),
+            KeyCode::Enter => {
+                let picked = picker
+                    .state
+                    .selected()
+                    .and_then(|i| picker.names.get(i))
+                    .cloned();
+                let failed = matches!(self.connection, ConnectionState::Failed(_));
+                if picked.is_some() && (picked != self.context || failed) {
+                    self.switch_to = Some(Switch {
+                        scope: self.scope.clone(),
+                        context: picked,
+                    });
+                }
+                self.dialog = None;
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_scale_key(&mut self, key: KeyCode) {
+        let Some(Dialog::Scale(dialog)) = &mut self.dialog else {
+            return;
+        };
+
+        match key {
+            KeyCode::Esc => self.dialog = None,
+            KeyCode::Char(c) if c.is_ascii_digit() && dialog.input.len() < 5 => {
+                dialog.input.push(c)
+            }
+            KeyCode::Backspace => {
+                dialog.input.pop();
+            }
+            KeyCode::Up | KeyCode::Char('+') => {
+                let n = dialog.input.parse::<i32>().unwrap_or(dialog.current);
+                dialog.input = (n + 1).to_string();
+            }
+            KeyCode::Down | KeyCode::Char('-') => {
+                let n = dialog.input.parse::<i32>().unwrap_or(dialog.current);
+                dialog.input = (n - 1).max(0).to_string();
+            }
+            KeyCode::Enter => {
+                let Ok(replicas) = dialog.input.parse::<i32>() else {
+                    return;
+                };
+                self.pending.push(Action::Scale {
+                    kind: dialog.kind,
+                    namespace: dialog.namespace.clone(),
+                    name: dialog.name.clone(),
+                    replicas,
+                });
+                self.dialog = None;
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_host_key_key(&mut self, key: KeyCode) {
+        match key {
+            KeyCode::Char('y') => {
+                let Some(Dialog::HostKey(dialog)) = self.dialog.take() else {
+                    return;
+                };
+                match dialog.key.trust() {
+                    Ok(()) => self.switch_to = Some(dialog.retry),
+                    Err(e) => self.error = Some(format!("{:#}", e)),
+                }
+            }
+            KeyCode::Esc | KeyCode::Char('n') => self.dialog = None,
+            _ => {}
+        }
+    }
+
+    fn handle_delete_key(&mut self, key: KeyCode) {
+        let Some(Dialog::Delete(dialog)) = &mut self.dialog else {
+            return;
+        };
+
+        match key {
+            KeyCode::Esc | KeyCode::Char('n') => self.dialog = None,
+            KeyCode::Char('f') => dialog.force = !dialog.force,
+            KeyCode::Char(c) if c.is_ascii_digit() && dialog.grace.len() < 5 => {
+                dialog.grace.push(c)
+            }
+            KeyCode::Backspace => {
+                dialog.grace.pop();
+            }
+            KeyCode::Char('y') | KeyCode::Enter => {
+                self.pending.push(Action::Delete {
+                    targets: dialog.targets.clone(),
+                    grace_period: dialog.grace.parse().ok(),
+                    force: dialog.force,
+                });
+                self.dialog = None;
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_rollout_key(&mut self, key: KeyCode) {
+        let Some(Dialog::Rollout(view)) = &mut self.dialog else {
+            return;
+        };
+
+        if let Some(diff) = &mut view.diff {
+            match key {
+                KeyCode::Esc | KeyCode::Char('q') => view.diff = None,
+                KeyCode::Down | KeyCode::Char('j') => {
+                    let max = diff.lines.len().saturating_sub(1) as u16;
+                    diff.scroll = (diff.scroll + 1).min(max);
+                }
+                KeyCode::Up | KeyCode::Char('k') => diff.scroll = diff.scroll.saturating_sub(1),
+                _ => {}
+            }
+            return;
+        }
+
+        match key {
+            KeyCode::Esc | KeyCode::Char('q') => self.dialog = None,
+            KeyCode::Down | KeyCode::Char('j') => {
+                scroll(&mut view.history_state, view.history.len(), 1)
+            }
+            KeyCode::Up | KeyCode::Char('k') => {
+                scroll(&mut view.history_state, view.history.len(), -1)
+            }
+            KeyCode::Char('R') => self.pending.push(Action::Restart {
+                targets: vec![ResourceRef {
+                    kind: ResourceKind::Deployment,
+                    namespace: view.namespace.clone(),
+                    name: view.name.clone(),
+                    uid: String::new(),
+                }],
+            }),
+            KeyCode::Char(' ') => {
+                let Some(rev) = view
+                    .history_state
+                    .selected()
+                    .and_then(|i| view.history.get(i))
+                else {
+                    return;
+                };
+                view.marked = if view.marked == Some(rev.revision) {
+                    None
+                } else {
+                    Some(rev.revision)
+                };
+            }
+            KeyCode::Char('d') => {
+                let Some(selected) = view
+                    .history_state
+                    .selected()
+                    .and_then(|i| view.history.get(i))
+                else {
+                    return;
+                };
+                // compare against the marked revision, or the live one
+                let base = match view.marked {
+                    Some(rev) => view.history.iter().find(|r| r.revision == rev),
+                    None => view.history.first(),
+                };
+                let Some(base) = base else {
+                    return;
+                };
+
+                let (old, new) = if base.revision <= selected.revision {
+                    (base, selected)
+                } else {
+                    (selected, base)
+                };
+                view.diff = Some(RevisionDiff {
+                    from: old.revision,
+                    to: new.revision,
+                    lines: rollout::diff_revisions(old, new),
+                    scroll: 0,
+                });
+            }
+            KeyCode::Char('u') => {
+                let Some(rev) = view
+                    .history_state
+                    .selected()
+                    .and_then(|i| view.history.get(i))
+                else {
+                    return;
+                };
+                self.pending.push(Action::Undo {
+                    namespace: view.namespace.clone(),
+                    name: view.name.clone(),
+                    revision: rev.revision,
+                });
+            }
+            _ => {}
+        }
+    }
+
+    fn matches_filter(&self, name: &str, namespace: &str, cluster: Option<&str>) -> bool {
+        let needle = self.filter.to_lowercase();
+        name.to_lowercase().contains(&needle)
+            || namespace.to_lowercase().contains(&needle)
+            || cluster.is_some_and(|c| c.to_lowercase().contains(&needle))
+    }
+
+    fn in_namespace(&self, namespace: &str) -> bool {
+        self.namespace.as_ref().is_none_or(|ns| ns == namespace)
+    }
+
+    /// Pods matching the current filter, in table order.
+    pub fn visible_pods(&self) -> Vec<&PodInfo> {
+        self.pods
+            .iter()
+            .filter(|p| self.matches_filter(&p.name, &p.namespace, p.cluster.as_deref()))
+            .filter(|p| self.in_namespace(&p.namespace))
+            .filter(|p| {
+                !self.problems_only || !matches!(p.status.as_str(), "Running" | "Succeeded")
+            })
+            .collect()
+    }
+
+    /// Workloads matching the current filter, in table order.
+    pub fn visible_deployments(&self) -> Vec<&DeploymentInfo> {
+        self.deployments
+            .iter()
+            .filter(|d| self.matches_filter(&d.name, &d.namespace, d.cluster.as_deref()))
+            .filter(|d| self.in_namespace(&d.namespace))
+            .filter(|d| !self.problems_only || d.ready < d.desired)
+            .collect()
+    }
+
+    /// Nodes matching the current filter, in table order.
+    pub fn visible_nodes(&self) -> Vec<&NodeInfo> {
+        self.nodes
+            .iter()
+            .filter(|n| self.matches_filter(&n.name, "", n.cluster.as_deref()))
+            .filter(|n| !self.problems_only || !n.status.starts_with("Ready"))
+            .collect()
+    }
+
+    fn visible_resources(&self) -> Vec<ResourceRef> {
+        match self.active_panel {
+            Panel::Pods => self.visible_pods().into_iter().map(pod_ref).collect(),
+            Panel::Deployments => self
+                .visible_deployments()
+                .into_iter()
+                .map(workload_ref)
+                .collect(),
+            // nodes are read-only
+            Panel::Nodes => Vec::new(),
+        }
+    }
+
+    fn selected_workload(&self) -> Option<&DeploymentInfo> {
+        if self.active_panel != Panel::Deployments {
+            return None;
+        }
+        self.deployments_state
+            .selected()
+            .and_then(|i| self.visible_deployments().get(i).copied())
+    }
+
+    fn selected_resource(&self) -> Option<ResourceRef> {
+        match self.active_panel {
+            Panel::Pods => self
+                .pods_state
+                .selected()
+                .and_then(|i| self.visible_pods().get(i).map(|p| pod_ref(p))),
+            Panel::Deployments => self.selected_workload().map(workload_ref),
+            Panel::Nodes => None,
+        }
+    }
+
+    /// Marked rows of the active panel, or the highlighted row if none are marked.
+    fn targets(&self) -> Vec<ResourceRef> {
+        let mut marked: Vec<ResourceRef> = self
+            .selection
+            .iter()
+            .filter(|r| panel_of(r.kind) == self.active_panel)
+            .cloned()
+            .collect();
+
+        if marked.is_empty() {
+            return self.selected_resource().into_iter().collect();
+        }
+        marked.sort_by(|a, b| (&a.namespace, &a.name).cmp(&(&b.namespace, &b.name)));
+        marked
+    }
+
+    fn toggle_mark(&mut self) {
+        let Some(target) = self.selected_resource() else {
+            return;
+        };
+        if !self.selection.remove(&target) {
+            self.selection.insert(target);
+        }
+        self.scroll_down();
+    }
+
+    /// Mark every visible row, or unmark them if they are all marked already.
+    fn mark_all(&mut self) {
+        let visible = self.visible_resources();
+        if visible.iter().all(|r| self.selection.contains(r)) {
+            for r in &visible {
+                self.selection.remove(r);
+            }
+        } else {
+            self.selection.extend(visible);
+        }
+    }
+
+    fn open_delete_dialog(&mut self) {
+        let targets = self.targets();
+        if targets.is_empty() {
+            return;
+        }
+        self.dialog = Some(Dialog::Delete(DeleteDialog {
+            targets,
+            grace: String::new(),
+            force: false,
+        }));
+    }
+
+    fn open_label_dialog(&mut self) {
+        let targets = self.targets();
+        if targets.is_empty() {
+            return;
+        }
+        self.dialog = Some(Dialog::Label(LabelDialog {
+            targets,
+            input: String::new(),
+        }));
+    }
+
+    fn open_log_view(&mut self) {
+        if self.active_panel != Panel::Pods {
+            return;
+        }
+        let pods = self.targets();
+        if pods.is_empty() {
+            return;
+        }
+        self.dialog = Some(Dialog::Logs(LogView {
+            pods,
+            containers: Vec::new(),
+            container: 0,
+            lines: Vec::new(),
+            scroll_back: 0,
+        }));
+        self.pending.push(Action::LoadLogs);
+    }
+
+    fn open_detail_view(&mut self) {
+        // usage history is only recorded for a single cluster
+        if self.scope == Some(Scope::All) {
+            self.message = Some("Pick a single cluster with c to see usage history".into());
+            return;
+        }
+        let view = match self.active_panel {
+            Panel::Pods => self
+                .pods_state
+                .selected()
+                .and_then(|i| self.visible_pods().get(i).copied())
+                .map(|p| DetailView::Pod {
+                    series: self
+                        .history
+                        .pod(&p.namespace, &p.name)
+                        .cloned()
+                        .unwrap_or_default(),
+                    pod: p.clone(),
+                }),
+            Panel::Nodes => self
+                .nodes_state
+                .selected()
+                .and_then(|i| self.visible_nodes().get(i).copied())
+                .map(|n| DetailView::Node {
+                    series: self.history.node(&n.name).cloned().unwrap_or_default(),
+                    node: n.clone(),
+                }),
+            Panel::Deployments => None,
+        };
+        if let Some(view) = view {
+            self.dialog = Some(Dialog::Detail(view));
+        }
+    }
+
+    fn restart_selected(&mut self) {
+        if self.active_panel != Panel::Deployments {
+            return;
+        }
+        let targets = self.targets();
+        if !targets.is_empty() {
+            self.pending.push(Action::Restart { targets });
+        }
+    }
+
+    fn open_rollout_view(&mut self) {
+        let Some(dep) = self.selected_workload() else {
+            return;
+        };
+        if dep.kind != WorkloadKind::Deployment {
+            self.message = Some("Rollout history is only available for deployments".into());
+            return;
+        }
+
+        self.dialog = Some(Dialog::Rollout(RolloutView {
+            namespace: dep.namespace.clone(),
+            name: dep.name.clone(),
+            status: None,
+            history: Vec::new(),
+            history_state: TableState::default(),
+            marked: None,
+            diff: None,
+        }));
+        self.pending.push(Action::LoadRollout);
+    }
+
+    fn open_cluster_picker(&mut self) {
+        if self.clusters.is_empty() {
+            self.message = Some("No [clusters] profiles in config.toml".into());
+            return;
+        }
+        let mut entries: Vec<Scope> = self.clusters.iter().cloned().map(Scope::Cluster).collect();
+        if self.clusters.len() > 1 {
+            entries.push(Scope::All);
+        }
+        let current = self
+            .scope
+            .as_ref()
+            .and_then(|scope| entries.iter().position(|e| e == scope));
+        self.dialog = Some(Dialog::Clusters(ClusterPicker {
+            entries,
+            current: self.scope.clone(),
+            state: TableState::default().with_selected(current.unwrap_or(0)),
+        }));
+    }
+
+    fn open_context_picker(&mut self) {
+        if self.scope == Some(Scope::All) {
+            self.message = Some("Pick a single cluster with c to change its context".into());
+            return;
+        }
+        if self.contexts.is_empty() {
+            self.message = Some("No contexts in the kubeconfig".into());
+            return;
+        }
+        let current = self
+            .context
+            .as_ref()
+            .and_then(|context| self.contexts.iter().position(|c| c == context));
+        self.dialog = Some(Dialog::Contexts(ContextPicker {
+            names: self.contexts.clone(),
+            current: self.context.clone(),
+            state: TableState::default().with_selected(current.unwrap_or(0)),
+        }));
+    }
+
+    fn open_scale_dialog(&mut self) {
+        let Some(dep) = self.selected_workload() else {
+            return;
+        };
+
+        self.dialog = Some(Dialog::Scale(ScaleDialog {
+            kind: dep.kind,
+            namespace: dep.namespace.clone(),
+            name: dep.name.clone(),
+            current: dep.desired,
+            input: dep.desired.to_string(),
+        }));
+    }
+
     fn toggle_panel(&mut self) {
         match self.active_panel {
             Panel::Pods => {
@@ -118,6 +1441,12 @@ impl App {
                 }
             }
             Panel::Deployments => {
+                self.active_panel = Panel::Nodes;
+                if self.nodes_state.selected().is_none() && !self.nodes.is_empty() {
+                    self.nodes_state.select(Some(0));
+                }
+            }
+            Panel::Nodes => {
                 self.active_panel = Panel::Pods;
                 if self.pods_state.selected().is_none() && !self.pods.is_empty() {
                     self.pods_state.select(Some(0));
@@ -128,36 +1457,577 @@ impl App {
 
     fn scroll_down(&mut self) {
         match self.active_panel {
-            Panel::Pods => scroll(&mut self.pods_state, self.pods.len(), 1),
-            Panel::Deployments => scroll(&mut self.deployments_state, self.deployments.len(), 1),
+            Panel::Pods => {
+                let len = self.visible_pods().len();
+                scroll(&mut self.pods_state, len, 1)
+            }
+            Panel::Deployments => {
+                let len = self.visible_deployments().len();
+                scroll(&mut self.deployments_state, len, 1)
+            }
+            Panel::Nodes => {
+                let len = self.visible_nodes().len();
+                scroll(&mut self.nodes_state, len, 1)
+            }
         }
     }
 
     fn scroll_up(&mut self) {
         match self.active_panel {
-            Panel::Pods => scroll(&mut self.pods_state, self.pods.len(), -1),
-            Panel::Deployments => scroll(&mut self.deployments_state, self.deployments.len(), -1),
+            Panel::Pods => {
+                let len = self.visible_pods().len();
+                scroll(&mut self.pods_state, len, -1)
+            }
+            Panel::Deployments => {
+                let len = self.visible_deployments().len();
+                scroll(&mut self.deployments_state, len, -1)
+            }
+            Panel::Nodes => {
+                let len = self.visible_nodes().len();
+                scroll(&mut self.nodes_state, len, -1)
+            }
+        }
+    }
+
+    async fn run_actions(&mut self, client: &Client) {
+        for action in std::mem::take(&mut self.pending) {
+            match action {
+                Action::Scale {
+                    kind,
+                    namespace,
+                    name,
+                    replicas,
+                } => match k8s::scale_workload(client, kind, &namespace, &name, replicas).await {
+                    Ok(()) => {
+                        self.message = Some(format!(
+                            "Scaling {}/{} to {} replicas...",
+                            namespace, name, replicas
+                        ));
+                        self.scaling.retain(|t| {
+                            !(t.kind == kind && t.namespace == namespace && t.name == name)
+                        });
+                        self.scaling.push(ScaleTracker {
+                            kind,
+                            namespace,
+                            name,
+                            target: replicas,
+                            started: Instant::now(),
+                        });
+                    }
+                    Err(e) => self.message = Some(format!("{:#}", e)),
+                },
+                Action::Restart { targets } => {
+                    let mut results = Vec::new();
+                    for target in targets {
+                        let res = rollout::restart(client, &target).await;
+                        results.push((target, res));
+                    }
+                    self.report("Restarted", results);
+                }
+                Action::Undo {
+                    namespace,
+                    name,
+                    revision,
+                } => {
+                    self.message = Some(
+                        match rollout::undo(client, &namespace, &name, revision).await {
+                            Ok(()) => {
+                                format!(
+                                    "Rolled back {}/{} to revision {}",
+                                    namespace, name, revision
+                                )
+                            }
+                            Err(e) => format!("{:#}", e),
+                        },
+                    );
+                }
+                // the refresh after every action loads the view
+                Action::LoadRollout => {}
+                Action::Delete {
+                    targets,
+                    grace_period,
+                    force,
+                } => {
+                    let mut results = Vec::new();
+                    for target in targets {
+                        let res = k8s::delete_resource(client, &target, grace_period, force).await;
+                        if res.is_ok() && !self.terminating.contains(&target) {
+                            self.terminating.push(target.clone());
+                        }
+                        results.push((target, res));
+                    }
+                    self.report("Deleting", results);
+                }
+                Action::Label {
+                    targets,
+                    key,
+                    value,
+                } => {
+                    let mut results = Vec::new();
+                    for target in targets {
+                        let res =
+                            k8s::label_resource(client, &target, &key, value.as_deref()).await;
+                        results.push((target, res));
+                    }
+                    self.report("Labeled", results);
+                }
+                // loaded by the refresh as well
+                Action::LoadLogs => {}
+            }
+        }
+    }
+
+    /// Summarize the outcome of an action: a header message for a single
+    /// resource, a per-resource results dialog for a bulk action.
+    fn report(&mut self, verb: &str, results: Vec<(ResourceRef, anyhow::Result<()>)>) {
+        if let [(target, res)] = results.as_slice() {
+            self.message = Some(match res {
+                Ok(()) => format!("{} {}", verb, target),
+                Err(e) => format!("{:#}", e),
+            });
+            return;
+        }
+
+        let ok = results.iter().filter(|(_, r)| r.is_ok()).count();
+        let title = format!("{} {} of {} resources", verb, ok, results.len());
+        self.message = Some(title.clone());
+        self.dialog = Some(Dialog::Results(ResultsView {
+            title,
+            results: results
+                .into_iter()
+                .map(|(t, r)| (t, r.map_err(|e| format!("{:#}", e))))
+                .collect(),
+            scroll: 0,
+        }));
+    }
+
+    /// Reload the tail of the logs for the open log view.
+    async fn refresh_logs(&mut self, client: &Client) {
+        let Some(Dialog::Logs(view)) = &mut self.dialog else {
+            return;
+        };
+
+        if view.containers.len() != view.pods.len() {
+            let mut containers = Vec::new();
+            for pod in &view.pods {
+                // without them the log request names no container and the API
+                // server explains what is wrong
+                containers.push(
+                    k8s::pod_containers(client, &pod.namespace, &pod.name)
+                        .await
+                        .unwrap_or_default(),
+                );
+            }
+            view.containers = containers;
+        }
+
+        // prefix lines with the pod name when tailing several pods
+        let prefixed = view.pods.len() > 1;
+        let mut lines = Vec::new();
+        for (pod, containers) in view.pods.iter().zip(&view.containers) {
+            let container = containers.get(view.container).or(containers.first());
+            let prefix = match container {
+                Some(container) if prefixed && containers.len() > 1 => {
+                    format!("[{}/{}] ", pod.name, container)
+                }
+                _ if prefixed => format!("[{}] ", pod.name),
+                _ => String::new(),
+            };
+            let container = container.map(String::as_str);
+            match k8s::tail_logs(client, &pod.namespace, &pod.name, container, LOG_TAIL_LINES).await
+            {
+                Ok(text) => lines.extend(text.lines().map(|l| format!("{}{}", prefix, l))),
+                Err(e) => lines.push(format!("{}{:#}", prefix, e)),
+            }
+        }
+
+        view.lines = lines;
+    }
+
+    /// Reload status and history for the open rollout view.
+    async fn refresh_rollout(&mut self, client: &Client) {
+        let Some(Dialog::Rollout(view)) = &mut self.dialog else {
+            return;
+        };
+
+        match rollout::status(client, &view.namespace, &view.name).await {
+            Ok(status) => view.status = Some(status),
+            Err(e) => self.error = Some(format!("rollout: {:#}", e)),
+        }
+
+        match rollout::history(client, &view.namespace, &view.name).await {
+            Ok(history) => {
+                if view.history_state.selected().is_none() && !history.is_empty() {
+                    view.history_state.select(Some(0));
+                }
+                view.history = history;
+            }
+            Err(e) => self.error = Some(format!("rollout: {:#}", e)),
+        }
+    }
+
+    /// Check scaled workloads against the latest READY counts.
+    fn track_scaling(&mut self) {
+        let mut done = Vec::new();
+        self.scaling.retain(|t| {
+            let converged = self.deployments.iter().any(|d| {
+                d.kind == t.kind
+                    && d.namespace == t.namespace
+                    && d.name == t.name
+                    && d.ready == t.target
+                    && d.up_to_date >= t.target
+            });
+            if converged {
+                done.push(format!("{}/{} scaled to {}", t.namespace, t.name, t.target));
+                false
+            } else if t.started.elapsed() >= SCALE_TIMEOUT {
+                done.push(format!(
+                    "{}/{} did not reach {} ready replicas within {}s",
+                    t.namespace,
+                    t.name,
+                    t.target,
+                    SCALE_TIMEOUT.as_secs()
+                ));
+                false
+            } else {
+                true
+            }
+        });
+
+        if let Some(msg) = done.pop() {
+            self.message = Some(msg);
         }
     }
 
+    /// Forget deleted resources once they no longer show up in the listings.
+    /// A resource recreated under the same name has a new uid and does not
+    /// keep the old one around.
+    fn track_terminating(&mut self) {
+        let pods = &self.pods;
+        let deployments = &self.deployments;
+        self.terminating.retain(|t| match t.kind {
+            ResourceKind::Pod => pods.iter().any(|p| p.uid == t.uid),
+            kind => deployments
+                .iter()
+                .any(|d| ResourceKind::from(d.kind) == kind && d.uid == t.uid),
+        });
+    }
+
     async fn refresh(&mut self, client: &Client) {
-        match k8s::fetch_pods(client).await {
+        // list only the shown namespace, users bound to it may not list more
+        let namespace = self.namespace.clone();
+        let namespace = namespace.as_deref();
+        match k8s::fetch_pods(client, namespace).await {
             Ok(pods) => {
                 self.pods = pods;
                 self.error = None;
             }
-            Err(e) => self.error = Some(format!("pods: {}", e)),
+            Err(e) => self.error = Some(format!("pods: {:#}", e)),
         }
 
-        match k8s::fetch_deployments(client).await {
-            Ok(deps) => {
+        match k8s::fetch_deployments(client, namespace).await {
+            Ok(mut deps) => {
+                match k8s::fetch_statefulsets(client, namespace).await {
+                    Ok(sets) => deps.extend(sets),
+                    Err(e) => self.error = Some(format!("statefulsets: {:#}", e)),
+                }
                 self.deployments = deps;
-                if self.error.is_none() {
-                    self.error = None;
+            }
+            Err(e) => self.error = Some(format!("deployments: {:#}", e)),
+        }
+
+        match k8s::fetch_nodes(client).await {
+            Ok(nodes) => {
+                self.nodes_available = nodes.is_some();
+                self.nodes = nodes.unwrap_or_default();
+            }
+            Err(e) => self.error = Some(format!("nodes: {:#}", e)),
+        }
+
+        self.refresh_metrics(client, namespace).await;
+        self.record_history();
+        self.track_scaling();
+        self.track_terminating();
+        self.prune_selection();
+        self.refresh_rollout(client).await;
+        self.refresh_logs(client).await;
+    }
+
+    /// Attach metrics-server usage to the freshly listed pods and nodes.
+    async fn refresh_metrics(&mut self, client: &Client, namespace: Option<&str>) {
+        let pods = k8s::metrics::fetch_pod_metrics(client, namespace).await;
+        let nodes = k8s::metrics::fetch_node_metrics(client).await;
+        if let (Err(e), _) | (_, Err(e)) = (&pods, &nodes) {
+            // keep an earlier error, it is usually the cause
+            if self.error.is_none() {
+                self.error = Some(format!("metrics: {:#}", e));
+            }
+            if pods.is_err() && nodes.is_err() {
+                return;
+            }
+        }
+        // usage of one kind still shows when the other fails
+        let (pods, nodes) = (pods.ok().flatten(), nodes.ok().flatten());
+        self.metrics_available = Some(pods.is_some() || nodes.is_some());
+        attach_usage(&mut self.pods, &mut self.nodes, pods, nodes);
+    }
+
+    /// Start listing every connected cluster of the all-clusters view in the
+    /// background, unless it is still busy with the last one. The listings
+    /// come in through [`App::take_listings`].
+    fn fetch_all(&mut self, clusters: &[Cluster]) {
+        for cluster in clusters {
+            let name = cluster.name.clone().unwrap_or_default();
+            if *cluster.state().borrow() != ConnectionState::Connected
+                || !self.fetching.insert(name.clone())
+            {
+                continue;
+            }
+            let client = cluster.client.clone();
+            let namespace = cluster.namespace.clone();
+            let listings = self.listings_tx.clone();
+            let generation = self.generation;
+            tokio::spawn(async move {
+                let listing = match timeout(FETCH_TIMEOUT, fetch_cluster(client, namespace)).await {
+                    Ok(listing) => listing.map_err(|e| format!("{:#}", e)),
+                    Err(_) => Err(format!("no answer within {}s", FETCH_TIMEOUT.as_secs())),
+                };
+                listings.send((generation, name, listing)).ok();
+            });
+        }
+    }
+
+    /// Take the listings that came in and rebuild the all-clusters tables.
+    /// A cluster that is down or fails to list keeps its last listing and
+    /// adds its error.
+    fn take_listings(&mut self, clusters: &[Cluster]) {
+        let mut changed = false;
+        while let Ok((generation, name, listing)) = self.listings_rx.try_recv() {
+            if generation != self.generation {
+                continue;
+            }
+            self.fetching.remove(&name);
+            changed = true;
+            match listing {
+                Ok(listing) => {
+                    self.listing_errors.remove(&name);
+                    let listing = listing.tagged(&name);
+                    self.listings.insert(name, listing);
+                }
+                Err(e) => {
+                    self.listing_errors.insert(name, e);
+                }
+            }
+        }
+        if changed {
+            self.show_listings(clusters);
+        }
+    }
+
+    /// Fill the tables from the last listing of every cluster, in profile
+    /// order, and collect what is wrong with each.
+    fn show_listings(&mut self, clusters: &[Cluster]) {
+        let mut errors = self.connect_errors.clone();
+        let mut warnings = Vec::new();
+        let mut pods = Vec::new();
+        let mut deployments = Vec::new();
+        let mut nodes = Vec::new();
+        let mut metrics = None;
+        for cluster in clusters {
+            let name = cluster.name.clone().unwrap_or_default();
+            let state = cluster.state().borrow().clone();
+            match state {
+                ConnectionState::Connected => {
+                    if let Some(error) = self.listing_errors.get(&name) {
+                        errors.push((name.clone(), error.clone()));
+                    }
                 }
+                ConnectionState::Connecting => errors.push((name.clone(), "connecting".into())),
+                ConnectionState::Reconnecting { attempt, error, .. } => errors.push((
+                    name.clone(),
+                    format!("{} (reconnecting, attempt {})", error, attempt),
+                )),
+                ConnectionState::Failed(error) => errors.push((name.clone(), error)),
             }
-            Err(e) => self.error = Some(format!("deployments: {}", e)),
+            if let Some(listing) = self.listings.get(&name) {
+                pods.extend(listing.pods.iter().cloned());
+                deployments.extend(listing.deployments.iter().cloned());
+                nodes.extend(listing.nodes.iter().cloned());
+                metrics = Some(metrics.unwrap_or(false) || listing.metrics);
+                warnings.extend(listing.warnings.iter().map(|w| (name.clone(), w.clone())));
+            }
+        }
+
+        self.pods = pods;
+        self.deployments = deployments;
+        self.nodes = nodes;
+        self.metrics_available = metrics;
+        self.cluster_errors = errors;
+        self.cluster_warnings = warnings;
+    }
+
+    /// Sample usage and restarts into the history, at most once per interval.
+    fn record_history(&mut self) {
+        if self
+            .last_sample
+            .is_some_and(|t| t.elapsed() < history::SAMPLE_INTERVAL)
+        {
+            return;
+        }
+        self.last_sample = Some(Instant::now());
+        self.history.record(&self.pods, &self.nodes);
+
+        // keep an open detail view current; it stays on the last data once gone
+        match &mut self.dialog {
+            Some(Dialog::Detail(DetailView::Pod { pod, series })) => {
+                if let Some(p) = self
+                    .pods
+                    .iter()
+                    .find(|p| p.namespace == pod.namespace && p.name == pod.name)
+                {
+                    *pod = p.clone();
+                }
+                if let Some(s) = self.history.pod(&pod.namespace, &pod.name) {
+                    *series = s.clone();
+                }
+            }
+            Some(Dialog::Detail(DetailView::Node { node, series })) => {
+                if let Some(n) = self.nodes.iter().find(|n| n.name == node.name) {
+                    *node = n.clone();
+                }
+                if let Some(s) = self.history.node(&node.name) {
+                    *series = s.clone();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Drop marks of resources that no longer exist.
+    fn prune_selection(&mut self) {
+        let pods = &self.pods;
+        let deployments = &self.deployments;
+        self.selection.retain(|r| match r.kind {
+            ResourceKind::Pod => pods.iter().any(|p| pod_ref(p) == *r),
+            _ => deployments.iter().any(|d| workload_ref(d) == *r),
+        });
+    }
+}
+
+/// Everything the all-clusters view lists from one cluster.
+struct Listing {
+    pods: Vec<PodInfo>,
+    deployments: Vec<DeploymentInfo>,
+    nodes: Vec<NodeInfo>,
+    /// Whether metrics-server answered.
+    metrics: bool,
+    /// What could not be listed, the rest still shows.
+    warnings: Vec<String>,
+}
+
+impl Listing {
+    /// Mark everything as coming from cluster `name`.
+    fn tagged(mut self, name: &str) -> Self {
+        let tag = Some(name.to_string());
+        for pod in &mut self.pods {
+            pod.cluster = tag.clone();
+        }
+        for dep in &mut self.deployments {
+            dep.cluster = tag.clone();
         }
+        for node in &mut self.nodes {
+            node.cluster = tag.clone();
+        }
+        self
+    }
+}
+
+/// List one cluster, limited to the namespace of its context if it sets one.
+async fn fetch_cluster(client: Client, namespace: Option<String>) -> Result<Listing> {
+    let namespace = namespace.as_deref();
+    let mut pods = k8s::fetch_pods(&client, namespace).await?;
+    let mut deployments = k8s::fetch_deployments(&client, namespace).await?;
+    deployments.extend(k8s::fetch_statefulsets(&client, namespace).await?);
+
+    // nodes and usage are optional here, pods and workloads still show
+    let mut warnings = Vec::new();
+    let mut nodes = match k8s::fetch_nodes(&client).await {
+        Ok(nodes) => nodes.unwrap_or_default(),
+        Err(e) => {
+            warnings.push(format!("nodes: {:#}", e));
+            Vec::new()
+        }
+    };
+    let pod_usage = match k8s::metrics::fetch_pod_metrics(&client, namespace).await {
+        Ok(usage) => usage,
+        Err(e) => {
+            warnings.push(format!("pod metrics: {:#}", e));
+            None
+        }
+    };
+    let node_usage = match k8s::metrics::fetch_node_metrics(&client).await {
+        Ok(usage) => usage,
+        Err(e) => {
+            warnings.push(format!("node metrics: {:#}", e));
+            None
+        }
+    };
+    let metrics = pod_usage.is_some() || node_usage.is_some();
+    attach_usage(&mut pods, &mut nodes, pod_usage, node_usage);
+
+    Ok(Listing {
+        pods,
+        deployments,
+        nodes,
+        metrics,
+        warnings,
+    })
+}
+
+/// Fill in the usage of listed pods and nodes from metrics-server.
+fn attach_usage(
+    pods: &mut [PodInfo],
+    nodes: &mut [NodeInfo],
+    pod_usage: Option<HashMap<(String, String), Usage>>,
+    node_usage: Option<HashMap<String, Usage>>,
+) {
+    if let Some(usage) = pod_usage {
+        for pod in pods {
+            pod.usage = usage
+                .get(&(pod.namespace.clone(), pod.name.clone()))
+                .copied();
+        }
+    }
+    if let Some(usage) = node_usage {
+        for node in nodes {
+            node.usage = usage.get(&node.name).copied();
+        }
+    }
+}
+
+fn pod_ref(p: &PodInfo) -> ResourceRef {
+    ResourceRef {
+        kind: ResourceKind::Pod,
+        namespace: p.namespace.clone(),
+        name: p.name.clone(),
+        uid: p.uid.clone(),
+    }
+}
+
+fn workload_ref(d: &DeploymentInfo) -> ResourceRef {
+    ResourceRef {
+        kind: d.kind.into(),
+        namespace: d.namespace.clone(),
+        name: d.name.clone(),
+        uid: d.uid.clone(),
+    }
+}
+
+/// Panel listing resources of the given kind.
+fn panel_of(kind: ResourceKind) -> Panel {
+    match kind {
+        ResourceKind::Pod => Panel::Pods,
+        ResourceKind::Deployment | ResourceKind::StatefulSet => Panel::Deployments,
     }
 }
 
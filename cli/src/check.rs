@@ -0,0 +1,417 @@
+use anyhow::{Result, bail};
+use serde::Deserializer;
+use serde::de::{DeserializeOwned, Visitor};
+use std::fmt;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use toml_edit::{ImDocument, Item, TableLike};
+
+use crate::cluster::Cluster;
+use crate::config::{Config, ConfigFile, DashboardConfig, KubernetesConfig, SshConfig};
+use crate::{k8s, ssh};
+
+/// `prism config check`: validate the config file at `path` (or the one
+/// found by [`ConfigFile::find`]) and the files it points to, for the profile
+/// `cluster` or every profile. With `connect`, also connect to each cluster.
+pub async fn run(path: Option<&Path>, cluster: Option<&str>, connect: bool) -> Result<()> {
+    let path = match path {
+        Some(path) => path.to_path_buf(),
+        None => ConfigFile::find()?,
+    };
+    let mut report = Report::default();
+    println!("Config {}", path.display());
+
+    let content = match std::fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(e) => {
+            report.error(format!("Failed to read {}: {}", path.display(), e));
+            return report.finish();
+        }
+    };
+    let doc = match ImDocument::parse(content.as_str()) {
+        Ok(doc) => doc,
+        Err(e) => {
+            let at = Location::new(&path, &content, e.span());
+            report.error(format!("{}: {}", at, e.message().trim_end()));
+            return report.finish();
+        }
+    };
+    report.ok("TOML syntax");
+
+    for (problem, span) in unknown_keys(doc.as_table()) {
+        report.warn(format!(
+            "{}: {}",
+            Location::new(&path, &content, span),
+            problem
+        ));
+    }
+
+    let file = match ConfigFile::load(Some(&path)) {
+        Ok(file) => file,
+        Err(e) => {
+            report.error(format!("{:#}", e));
+            return report.finish();
+        }
+    };
+    let names: Vec<Option<String>> = match cluster {
+        Some(name) => vec![Some(name.to_string())],
+        None if file.cluster_names().is_empty() => vec![None],
+        None => file.cluster_names().into_iter().map(Some).collect(),
+    };
+
+    for name in names {
+        if let Some(name) = &name {
+            println!("Cluster {}", name);
+        }
+        let cfg = match file.cluster(name.as_deref()) {
+            Ok(cfg) => cfg,
+            Err(e) => {
+                report.error(settings_error(&e, &path, &content, &doc, name.as_deref()));
+                continue;
+            }
+        };
+        report.ok("Settings");
+        check_files(&cfg, &mut report).await;
+        if connect {
+            check_connection(&cfg, &mut report).await;
+        }
+    }
+
+    report.finish()
+}
+
+/// Running count of what the check found, printed as it goes.
+#[derive(Default)]
+struct Report {
+    errors: usize,
+    warnings: usize,
+}
+
+impl Report {
+    fn ok(&mut self, what: impl fmt::Display) {
+        println!("  ✓ {}", what);
+    }
+
+    fn warn(&mut self, what: impl fmt::Display) {
+        self.warnings += 1;
+        println!("  ! {}", what);
+    }
+
+    fn error(&mut self, what: impl fmt::Display) {
+        self.errors += 1;
+        println!("  ✗ {}", what);
+    }
+
+    fn finish(self) -> Result<()> {
+        println!();
+        if self.errors > 0 {
+            bail!(
+                "{} error(s), {} warning(s) in the config",
+                self.errors,
+                self.warnings
+            );
+        }
+        println!("Config OK, {} warning(s)", self.warnings);
+        Ok(())
+    }
+}
+
+/// `file:line:column` of a byte offset in the config, or just the file.
+struct Location<'a> {
+    path: &'a Path,
+    line_col: Option<(usize, usize)>,
+}
+
+impl<'a> Location<'a> {
+    fn new(path: &'a Path, content: &str, span: Option<Range<usize>>) -> Self {
+        let line_col = span.map(|span| {
+            let before = &content[..span.start.min(content.len())];
+            let line = before.matches('\n').count() + 1;
+            let column = before.rsplit('\n').next().unwrap_or("").chars().count() + 1;
+            (line, column)
+        });
+        Location { path, line_col }
+    }
+}
+
+impl fmt::Display for Location<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.line_col {
+            Some((line, column)) => write!(f, "{}:{}:{}", self.path.display(), line, column),
+            None => write!(f, "{}", self.path.display()),
+        }
+    }
+}
+
+/// Keys no section knows about, described with where they are.
+fn unknown_keys(root: &dyn TableLike) -> Vec<(String, Option<Range<usize>>)> {
+    let mut unknown = Vec::new();
+    let mut top = fields::<Config>().to_vec();
+    top.extend(["clusters", "default_cluster", "dashboard"]);
+    check_keys(root, "", &top, &mut unknown);
+    check_sections(root, "", &mut unknown);
+    if let Some(dashboard) = root.get("dashboard").and_then(Item::as_table_like) {
+        check_keys(
+            dashboard,
+            "dashboard.",
+            fields::<DashboardConfig>(),
+            &mut unknown,
+        );
+    }
+
+    if let Some(clusters) = root.get("clusters").and_then(Item::as_table_like) {
+        for (name, profile) in clusters.iter() {
+            let Some(profile) = profile.as_table_like() else {
+                continue;
+            };
+            let prefix = format!("clusters.{}.", name);
+            check_keys(profile, &prefix, fields::<Config>(), &mut unknown);
+            check_sections(profile, &prefix, &mut unknown);
+        }
+    }
+    unknown
+}
+
+/// Look for unknown keys in the `[ssh]` and `[kubernetes]` sections of `table`.
+fn check_sections(
+    table: &dyn TableLike,
+    prefix: &str,
+    unknown: &mut Vec<(String, Option<Range<usize>>)>,
+) {
+    let sections = [
+        ("ssh", fields::<SshConfig>()),
+        ("kubernetes", fields::<KubernetesConfig>()),
+    ];
+    for (key, known) in sections {
+        if let Some(section) = table.get(key).and_then(Item::as_table_like) {
+            check_keys(section, &format!("{}{}.", prefix, key), known, unknown);
+        }
+    }
+}
+
+fn check_keys(
+    table: &dyn TableLike,
+    prefix: &str,
+    known: &[&str],
+    unknown: &mut Vec<(String, Option<Range<usize>>)>,
+) {
+    for (key, _) in table.iter() {
+        if known.contains(&key) {
+            continue;
+        }
+        let close = known
+            .iter()
+            .find(|k| strsim::damerau_levenshtein(k, key) <= 2);
+        let problem = match close {
+            Some(close) => format!("unknown key `{}{}`, did you mean `{}`?", prefix, key, close),
+            None => format!("unknown key `{}{}`", prefix, key),
+        };
+        unknown.push((problem, table.key(key).and_then(|k| k.span())));
+    }
+}
+
+/// Keys a config section accepts, taken from its `Deserialize` impl.
+fn fields<T: DeserializeOwned>() -> &'static [&'static str] {
+    let mut fields: &'static [&'static str] = &[];
+    T::deserialize(FieldNames(&mut fields)).ok();
+    fields
+}
+
+/// Deserializer that only records the field names it is asked for.
+struct FieldNames<'a>(&'a mut &'static [&'static str]);
+
+impl<'de> Deserializer<'de> for FieldNames<'_> {
+    type Error = serde::de::value::Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, _: V) -> Result<V::Value, Self::Error> {
+        Err(serde::de::Error::custom("not a struct"))
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        fields: &'static [&'static str],
+        _: V,
+    ) -> Result<V::Value, Self::Error> {
+        *self.0 = fields;
+        Err(serde::de::Error::custom("fields recorded"))
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map enum identifier ignored_any
+    }
+}
+
+/// Why the settings of a cluster are invalid, pointing at the offending key
+/// when the error names one: in the profile if it sets it, else in the
+/// shared sections.
+fn settings_error(
+    error: &anyhow::Error,
+    path: &Path,
+    content: &str,
+    doc: &ImDocument<&str>,
+    cluster: Option<&str>,
+) -> String {
+    let Some(toml_error) = error
+        .chain()
+        .find_map(|e| e.downcast_ref::<toml::de::Error>())
+    else {
+        return format!("{:#}", error);
+    };
+    // the key path is only part of the rendered error
+    let rendered = toml_error.to_string();
+    let Some(key) = rendered
+        .lines()
+        .find_map(|line| line.strip_prefix("in `")?.strip_suffix('`'))
+    else {
+        return format!(
+            "{}: {}",
+            Location::new(path, content, None),
+            toml_error.message()
+        );
+    };
+
+    let keys: Vec<&str> = key.split('.').collect();
+    let mut candidates = Vec::new();
+    if let Some(cluster) = cluster {
+        let mut profile = vec!["clusters", cluster];
+        profile.extend(&keys);
+        candidates.push(profile);
+    }
+    candidates.push(keys);
+    let span = candidates
+        .iter()
+        .find_map(|keys| span_of(doc.as_table(), keys));
+    format!(
+        "{}: {} (`{}`)",
+        Location::new(path, content, span),
+        toml_error.message(),
+        key
+    )
+}
+
+/// Where the value at `keys` is, or its key when the value has no span of
+/// its own, such as a `[section]`.
+fn span_of(table: &dyn TableLike, keys: &[&str]) -> Option<Range<usize>> {
+    let (first, rest) = keys.split_first()?;
+    let (key, item) = table.get_key_value(first)?;
+    if rest.is_empty() {
+        return item.span().or_else(|| key.span());
+    }
+    span_of(item.as_table_like()?, rest)
+}
+
+/// Check that the kubeconfig, the ssh config and the key files can be read.
+async fn check_files(cfg: &Config, report: &mut Report) {
+    match &cfg.kubernetes.remote_kubeconfig {
+        Some(path) => println!(
+            "  - kubeconfig {} is on the SSH host, checked with --connect",
+            path
+        ),
+        None => {
+            let source = match cfg.kubeconfig_path() {
+                Some(path) => path.display().to_string(),
+                None if std::env::var_os("KUBECONFIG").is_some_and(|v| !v.is_empty()) => {
+                    "from $KUBECONFIG".to_string()
+                }
+                None => ssh::expand_home("~/.kube/config").display().to_string(),
+            };
+            match k8s::load_kubeconfig(cfg, None, false).await {
+                Ok(kubeconfig) => {
+                    report.ok(format!(
+                        "kubeconfig {}, {} context(s)",
+                        source,
+                        kubeconfig.contexts.len()
+                    ));
+                    check_context(cfg, &kubeconfig, report);
+                }
+                Err(e) => report.error(format!("kubeconfig {}: {:#}", source, e)),
+            }
+        }
+    }
+
+    let Some(ssh_config) = &cfg.ssh else {
+        return;
+    };
+    let target = match ssh::Target::resolve(ssh_config) {
+        Ok(target) => target,
+        Err(e) => {
+            report.error(format!("SSH config: {:#}", e));
+            return;
+        }
+    };
+    report.ok(format!("SSH target {}", target));
+
+    let mut keys: Vec<&PathBuf> = Vec::new();
+    for hop in target.jumps.iter().chain([&target.host]) {
+        for key in &hop.identity_files {
+            if !keys.contains(&key) {
+                keys.push(key);
+            }
+        }
+    }
+    // keys named only in the ssh config are skipped when missing, as ssh does
+    let own_keys = target.own_keys();
+    for key in keys {
+        match std::fs::File::open(key) {
+            Ok(_) => report.ok(format!("key {}", key.display())),
+            Err(e) if own_keys.contains(key) => {
+                report.error(format!("key {}: {}", key.display(), e))
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => report.warn(format!("key {} from the ssh config: {}", key.display(), e)),
+        }
+    }
+    match &target.host.agent {
+        Some(socket) if !socket.exists() => report.warn(format!(
+            "ssh-agent socket {} does not exist",
+            socket.display()
+        )),
+        None if !target.host.identity_files.iter().any(|key| key.exists()) => {
+            report.warn("No SSH keys found and ssh-agent is off, only passwords can work")
+        }
+        _ => {}
+    }
+    if !target.known_hosts.exists() {
+        report.warn(format!(
+            "known_hosts {} does not exist yet",
+            target.known_hosts.display()
+        ));
+    }
+}
+
+/// Check that the configured context is in the kubeconfig, or that the
+/// kubeconfig names one.
+fn check_context(cfg: &Config, kubeconfig: &kube::config::Kubeconfig, report: &mut Report) {
+    let names = k8s::context_names(kubeconfig);
+    match &cfg.kubernetes.context {
+        Some(context) if !names.contains(context) => report.error(format!(
+            "context {} is not in the kubeconfig; available: {}",
+            context,
+            names.join(", ")
+        )),
+        Some(context) => report.ok(format!("context {}", context)),
+        None => match &kubeconfig.current_context {
+            Some(context) => report.ok(format!("context {} (current-context)", context)),
+            None => report.warn("The kubeconfig has no current-context, set kubernetes.context"),
+        },
+    }
+}
+
+/// Connect like the dashboard does, then ask the API server for its version.
+async fn check_connection(cfg: &Config, report: &mut Report) {
+    let cluster = match Cluster::connect(cfg, true, |step| println!("    {}", step)).await {
+        Ok(cluster) => cluster,
+        Err(e) => {
+            report.error(format!("{:#}", e));
+            return;
+        }
+    };
+    match cluster.client.apiserver_version().await {
+        Ok(version) => report.ok(format!("API server {}", version.git_version)),
+        Err(e) => report.error(format!("API server: {}", e)),
+    }
+    cluster.close().await;
+}
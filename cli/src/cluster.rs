@@ -0,0 +1,122 @@
+use anyhow::{Context, Result};
+use kube::Client;
+use std::sync::Arc;
+use tokio::sync::watch;
+
+use crate::config::Config;
+use crate::k8s;
+use crate::ssh::{self, ConnectionState};
+
+/// A connected cluster: the API client and the SSH tunnel it goes through.
+pub struct Cluster {
+    /// Profile name, `None` for a config without `[clusters]`.
+    pub name: Option<String>,
+    pub client: Client,
+    /// Contexts in the kubeconfig, to switch between.
+    pub contexts: Vec<String>,
+    /// Context in use, `None` when the kubeconfig names none.
+    pub context: Option<String>,
+    /// Default namespace of the context in use.
+    pub namespace: Option<String>,
+    /// Shared with the other clusters behind the same SSH settings.
+    tunnel: Option<Arc<ssh::Supervisor>>,
+}
+
+impl Cluster {
+    /// Read the kubeconfig, bring up the tunnel and build the client,
+    /// reporting each step to `progress`. With `interactive`, unknown host
+    /// keys may be confirmed on the terminal.
+    pub async fn connect(
+        cfg: &Config,
+        interactive: bool,
+        progress: impl Fn(String) + Send,
+    ) -> Result<Self> {
+        // connect ssh tunnel, unless the API server is reached directly
+        let target = match &cfg.ssh {
+            Some(ssh) => Some(ssh::Target::resolve(ssh).context("Failed to read SSH config")?),
+            None => None,
+        };
+        match &target {
+            Some(target) => progress(format!("Connecting to {}...", target)),
+            None => progress("No [ssh] section, connecting to the API server directly...".into()),
+        }
+        if let (Some(path), Some(target)) = (&cfg.kubernetes.remote_kubeconfig, &target) {
+            progress(format!(
+                "Reading kubeconfig {} from {}...",
+                path, target.host.hostname
+            ));
+        }
+        let kubeconfig = k8s::load_kubeconfig(cfg, target.as_ref(), interactive).await?;
+        let contexts = k8s::context_names(&kubeconfig);
+        let (context, namespace) = k8s::selected_context(cfg, &kubeconfig);
+        if let Some(context) = &cfg.kubernetes.context {
+            progress(format!("Using context {}", context));
+        }
+
+        let tunnel = match target {
+            Some(target) => {
+                let forward = if target.ssh.dynamic_forward {
+                    None
+                } else {
+                    Some(ssh::Forward {
+                        host: match &cfg.kubernetes.remote_host {
+                            Some(host) => host.clone(),
+                            None => k8s::server_host(cfg, &kubeconfig).await?,
+                        },
+                        port: match cfg.kubernetes.api_port {
+                            Some(port) => port,
+                            None => k8s::server_port(cfg, &kubeconfig).await?,
+                        },
+                    })
+                };
+                let tunnel =
+                    ssh::Supervisor::share(target, cfg.kubernetes.local_port, forward, interactive)
+                        .await
+                        .context("Failed to establish SSH tunnel")?;
+                match tunnel.endpoint() {
+                    ssh::Endpoint::Local(port) => {
+                        progress(format!("Tunnel ready on localhost:{}", port))
+                    }
+                    ssh::Endpoint::Socks(port) => {
+                        progress(format!("SOCKS proxy ready on localhost:{}", port))
+                    }
+                    _ => progress("SSH session ready".into()),
+                }
+                Some(tunnel)
+            }
+            None => None,
+        };
+        let endpoint = tunnel
+            .as_ref()
+            .map_or(ssh::Endpoint::Direct, |tunnel| tunnel.endpoint());
+
+        // build k8s client
+        let client = k8s::build_client(cfg, kubeconfig, &endpoint)
+            .await
+            .context("Failed to connect to Kubernetes API")?;
+
+        Ok(Cluster {
+            name: cfg.name.clone(),
+            client,
+            contexts,
+            context,
+            namespace,
+            tunnel,
+        })
+    }
+
+    /// State of the tunnel; a direct connection is never reported as lost.
+    pub fn state(&self) -> watch::Receiver<ConnectionState> {
+        match &self.tunnel {
+            Some(tunnel) => tunnel.state(),
+            None => watch::channel(ConnectionState::Connected).1,
+        }
+    }
+
+    /// Shut the tunnel down, unless another cluster still uses it.
+    pub async fn close(self) {
+        if let Some(tunnel) = self.tunnel {
+            tunnel.release().await.ok();
+        }
+    }
+}
@@ -1,73 +1,516 @@
-use anyhow::{Context, Result};
+use anyhow::{Context, Result, bail};
 use serde::Deserialize;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use toml::Table;
 
+/// The config file: shared `[ssh]`/`[kubernetes]` settings and named
+/// `[clusters.<name>]` profiles that override them.
+pub struct ConfigFile {
+    /// Where the file was read from.
+    pub path: PathBuf,
+    shared: Table,
+    clusters: Table,
+    default_cluster: Option<String>,
+    pub dashboard: DashboardConfig,
+}
+
+/// Settings for one cluster, with the shared defaults applied.
 #[derive(Debug, Deserialize)]
 pub struct Config {
-    pub ssh: SshConfig,
+    /// Profile name, `None` for a file without `[clusters]`.
+    #[serde(skip)]
+    pub name: Option<String>,
+    /// Directory of the config file, relative paths are resolved against it.
+    #[serde(skip)]
+    pub dir: PathBuf,
+    /// Without it the API server is reached directly.
+    pub ssh: Option<SshConfig>,
+    #[serde(default)]
     pub kubernetes: KubernetesConfig,
 }
 
-#[derive(Debug, Deserialize)]
+/// SSH settings. Anything left out is taken from `~/.ssh/config` when `host`
+/// matches one of its `Host` entries, otherwise ssh's defaults apply.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
 pub struct SshConfig {
+    /// Hostname or an alias from the ssh config.
     pub host: String,
-    pub user: String,
-    #[serde(default = "default_ssh_port")]
-    pub port: u16,
+    pub user: Option<String>,
+    pub port: Option<u16>,
     pub key_path: Option<String>,
+    /// Further keys, tried after `key_path`.
+    #[serde(default)]
+    pub identity_files: Vec<String>,
+    /// Only use the configured keys, not every key in the agent.
+    pub identities_only: Option<bool>,
+    /// Jump hosts, `[user@]host[:port]` separated by commas.
+    pub proxy_jump: Option<String>,
+    /// Authenticate with keys from ssh-agent.
+    #[serde(default = "default_agent")]
+    pub agent: bool,
+    /// Seconds between keepalives, 0 to disable.
+    pub keepalive_interval: Option<u64>,
+    /// Unanswered keepalives before the connection counts as lost.
+    pub keepalive_count_max: Option<usize>,
+    /// ssh config file, `~/.ssh/config` by default.
+    pub config_file: Option<String>,
+    #[serde(default)]
+    pub host_key_checking: HostKeyChecking,
+    /// known_hosts file, `~/.ssh/known_hosts` by default.
+    pub known_hosts: Option<String>,
+    /// Expected host key of `host`, e.g. `SHA256:...`, checked instead of
+    /// known_hosts.
+    pub host_key_fingerprint: Option<String>,
+    #[serde(default)]
+    pub transport: SshTransport,
+    /// Reach the API server at its kubeconfig address through an SSH SOCKS
+    /// proxy (`ssh -D`) instead of forwarding `remote_host:api_port`.
+    #[serde(default)]
+    pub dynamic_forward: bool,
 }
 
-#[derive(Debug, Deserialize)]
+/// How the SSH connection is made.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SshTransport {
+    /// In-process SSH client, channels opened per API connection.
+    #[default]
+    Native,
+    /// The system `ssh` binary with a local port forward.
+    OpenSsh,
+}
+
+/// What to do with host keys that are not in known_hosts yet.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum HostKeyChecking {
+    /// Show the fingerprint and ask before trusting it.
+    #[default]
+    Ask,
+    /// Refuse to connect.
+    Strict,
+    /// Trust and record it, like `StrictHostKeyChecking=accept-new`.
+    AcceptNew,
+}
+
+#[derive(Debug, Default, Deserialize)]
 pub struct KubernetesConfig {
-    pub kubeconfig: String,
-    #[serde(default = "default_api_port")]
-    pub api_port: u16,
-    #[serde(default = "default_local_port")]
-    pub local_port: u16,
+    /// Local kubeconfig; `$KUBECONFIG` or `~/.kube/config` when neither this
+    /// nor `remote_kubeconfig` is set.
+    pub kubeconfig: Option<String>,
+    /// Path of the kubeconfig on the SSH host, read over SSH instead of
+    /// `kubeconfig` and only kept in memory.
+    pub remote_kubeconfig: Option<String>,
+    /// Kubeconfig context to use instead of its current-context.
+    pub context: Option<String>,
+    /// API server host as reached from the SSH host; defaults to the host of
+    /// the kubeconfig server URL.
+    pub remote_host: Option<String>,
+    /// API server port as reached from the SSH host; defaults to the port of
+    /// the kubeconfig server URL.
+    pub api_port: Option<u16>,
+    /// Local end of the openssh port forward; a free port is picked when unset.
+    pub local_port: Option<u16>,
+    /// Name the API server certificate must be valid for; defaults to the
+    /// host of the kubeconfig server URL.
+    pub tls_server_name: Option<String>,
+    /// Existing SOCKS5 (`socks5://`) or HTTP CONNECT (`http://`) proxy for
+    /// API connections, instead of an SSH tunnel.
+    pub proxy_url: Option<String>,
 }
 
-fn default_ssh_port() -> u16 {
-    22
+/// `[dashboard]` settings; they apply to every cluster, profiles cannot
+/// override them.
+#[derive(Debug, Deserialize)]
+pub struct DashboardConfig {
+    /// Minutes of usage and restart history kept per pod and node.
+    #[serde(default = "default_history_minutes")]
+    pub history_minutes: u64,
 }
 
-fn default_api_port() -> u16 {
-    6443
+impl Default for DashboardConfig {
+    fn default() -> Self {
+        DashboardConfig {
+            history_minutes: default_history_minutes(),
+        }
+    }
 }
 
-fn default_local_port() -> u16 {
-    16443
+fn default_history_minutes() -> u64 {
+    10
 }
 
-impl Config {
-    pub fn load() -> Result<Self> {
-        let config_path = Self::config_path();
+fn default_agent() -> bool {
+    true
+}
+
+impl ConfigFile {
+    /// Read the file at `path`, or the first one found by [`ConfigFile::find`].
+    pub fn load(path: Option<&Path>) -> Result<Self> {
+        let config_path = match path {
+            Some(path) => path.to_path_buf(),
+            None => Self::find()?,
+        };
         let content = std::fs::read_to_string(&config_path)
             .with_context(|| format!("Failed to read config at {}", config_path.display()))?;
-        let config: Config =
-            toml::from_str(&content).with_context(|| "Failed to parse config.toml")?;
+        Self::parse(config_path, &content)
+    }
+
+    /// Parse `content` as the config file at `path`.
+    pub fn parse(path: PathBuf, content: &str) -> Result<Self> {
+        let mut shared: Table = toml::from_str(content)
+            .with_context(|| format!("Failed to parse {}", path.display()))?;
+
+        let clusters = match shared.remove("clusters") {
+            Some(toml::Value::Table(clusters)) => clusters,
+            Some(_) => bail!("clusters must be a table of [clusters.<name>] sections"),
+            None => Table::new(),
+        };
+        let default_cluster = match shared.remove("default_cluster") {
+            Some(toml::Value::String(name)) => Some(name),
+            Some(_) => bail!("default_cluster must be a string"),
+            None => None,
+        };
+        let dashboard: DashboardConfig = match shared.remove("dashboard") {
+            Some(dashboard) => dashboard
+                .try_into()
+                .context("Invalid [dashboard] settings")?,
+            None => DashboardConfig::default(),
+        };
+        if dashboard.history_minutes == 0 {
+            bail!("dashboard.history_minutes must be at least 1");
+        }
+        if let Some(name) = &default_cluster
+            && !clusters.contains_key(name)
+        {
+            bail!(
+                "default_cluster {} has no [clusters.{}] section",
+                name,
+                name
+            );
+        }
+
+        Ok(ConfigFile {
+            path,
+            shared,
+            clusters,
+            default_cluster,
+            dashboard,
+        })
+    }
+
+    /// `$PRISM_CONFIG` if set, otherwise the first existing `prism/config.toml`
+    /// in `$XDG_CONFIG_HOME` and the platform config directory.
+    pub fn find() -> Result<PathBuf> {
+        if let Some(path) = Self::env_path() {
+            return Ok(path);
+        }
+        let candidates = Self::candidates();
+        match candidates.iter().find(|path| path.is_file()) {
+            Some(path) => Ok(path.clone()),
+            None => bail!(
+                "No config file found, looked for {}; pass --config or set PRISM_CONFIG",
+                candidates
+                    .iter()
+                    .map(|p| p.display().to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        }
+    }
+
+    /// Where a new config goes: `$PRISM_CONFIG` if set, otherwise
+    /// `prism/config.toml` in the first config directory.
+    pub fn default_path() -> Result<PathBuf> {
+        if let Some(path) = Self::env_path() {
+            return Ok(path);
+        }
+        Self::candidates()
+            .into_iter()
+            .next()
+            .context("No config directory found; pass --config")
+    }
+
+    fn env_path() -> Option<PathBuf> {
+        std::env::var_os("PRISM_CONFIG")
+            .filter(|p| !p.is_empty())
+            .map(PathBuf::from)
+    }
+
+    /// `prism/config.toml` in `$XDG_CONFIG_HOME` and the platform config
+    /// directory, without duplicates.
+    fn candidates() -> Vec<PathBuf> {
+        let mut candidates: Vec<PathBuf> = Vec::new();
+        let xdg = std::env::var_os("XDG_CONFIG_HOME")
+            .filter(|p| !p.is_empty())
+            .map(PathBuf::from);
+        for dir in xdg.into_iter().chain(dirs::config_dir()) {
+            let path = dir.join("prism").join("config.toml");
+            if !candidates.contains(&path) {
+                candidates.push(path);
+            }
+        }
+        candidates
+    }
+
+    /// Profile names in file order.
+    pub fn cluster_names(&self) -> Vec<String> {
+        self.clusters.keys().cloned().collect()
+    }
+
+    /// Settings for the cluster `name`, or the default one. Without a
+    /// default, a single profile is picked on its own; a file without
+    /// profiles is one cluster.
+    pub fn cluster(&self, name: Option<&str>) -> Result<Config> {
+        let name = match name.or(self.default_cluster.as_deref()) {
+            Some(name) => Some(name),
+            None if self.clusters.len() == 1 => self.clusters.keys().next().map(String::as_str),
+            None if self.clusters.is_empty() => None,
+            None => bail!(
+                "Several clusters configured, pick one with --cluster or set default_cluster: {}",
+                self.cluster_names().join(", ")
+            ),
+        };
+
+        let mut table = self.shared.clone();
+        if let Some(name) = name {
+            let profile = match self.clusters.get(name) {
+                Some(toml::Value::Table(profile)) => profile,
+                Some(_) => bail!("clusters.{} must be a table", name),
+                None => bail!(
+                    "No cluster named {}; configured: {}",
+                    name,
+                    self.cluster_names().join(", ")
+                ),
+            };
+            drop_alternatives(&mut table, profile);
+            merge(&mut table, profile.clone());
+            // `ssh = false` drops the shared [ssh] section for this cluster
+            if table.get("ssh") == Some(&toml::Value::Boolean(false)) {
+                table.remove("ssh");
+            }
+        }
+
+        let mut config: Config = table.try_into().with_context(|| match name {
+            Some(name) => format!("Invalid settings for cluster {}", name),
+            None => "Invalid settings in config.toml".to_string(),
+        })?;
+        config.name = name.map(str::to_string);
+        config.dir = self
+            .path
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_default();
+        config.validate()?;
         Ok(config)
     }
+}
+
+/// Deep-merge `over` into `base`; tables are merged key by key, anything
+/// else is replaced.
+fn merge(base: &mut Table, over: Table) {
+    for (key, value) in over {
+        match (base.get_mut(&key), value) {
+            (Some(toml::Value::Table(base)), toml::Value::Table(over)) => merge(base, over),
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+/// Settings that replace each other, as dotted key paths.
+const ALTERNATIVES: [(&str, &str); 2] = [
+    ("kubernetes.kubeconfig", "kubernetes.remote_kubeconfig"),
+    ("kubernetes.proxy_url", "ssh"),
+];
+
+/// Remove the shared settings a profile replaces by setting an alternative,
+/// e.g. the shared `kubeconfig` of a profile with a `remote_kubeconfig`.
+fn drop_alternatives(base: &mut Table, profile: &Table) {
+    for (a, b) in ALTERNATIVES {
+        for (set, replaced) in [(a, b), (b, a)] {
+            // `ssh = false` already removes [ssh], it replaces nothing
+            let is_set =
+                lookup(profile, set).is_some_and(|value| value != &toml::Value::Boolean(false));
+            if is_set && let Some((section, key)) = parent_of(base, replaced) {
+                section.remove(key);
+            }
+        }
+    }
+}
+
+fn lookup<'a>(table: &'a Table, path: &str) -> Option<&'a toml::Value> {
+    let (first, rest) = path.split_once('.').unwrap_or((path, ""));
+    let value = table.get(first)?;
+    match (rest, value) {
+        ("", value) => Some(value),
+        (rest, toml::Value::Table(table)) => lookup(table, rest),
+        _ => None,
+    }
+}
 
-    fn config_path() -> PathBuf {
-        let mut dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
-        dir.push("config.toml");
-        dir
+/// The table holding the last key of `path`, with that key.
+fn parent_of<'a, 'p>(table: &'a mut Table, path: &'p str) -> Option<(&'a mut Table, &'p str)> {
+    match path.split_once('.') {
+        None => Some((table, path)),
+        Some((first, rest)) => match table.get_mut(first)? {
+            toml::Value::Table(table) => parent_of(table, rest),
+            _ => None,
+        },
     }
+}
 
-    /// Resolve the kubeconfig path relative to the project directory,
-    /// or as an absolute / `~`-expanded path.
-    pub fn kubeconfig_path(&self) -> PathBuf {
-        let raw = &self.kubernetes.kubeconfig;
-        if let Some(stripped) = raw.strip_prefix("~/") {
-            if let Some(home) = dirs::home_dir() {
-                return home.join(stripped);
+impl Config {
+    fn validate(&self) -> Result<()> {
+        match (
+            &self.kubernetes.kubeconfig,
+            &self.kubernetes.remote_kubeconfig,
+        ) {
+            (Some(_), Some(_)) => {
+                bail!("Set only one of kubernetes.kubeconfig and kubernetes.remote_kubeconfig")
+            }
+            (None, Some(_)) if self.ssh.is_none() => {
+                bail!("kubernetes.remote_kubeconfig needs an [ssh] section")
             }
+            _ => {}
+        }
+        if self.ssh.is_some() && self.kubernetes.proxy_url.is_some() {
+            bail!("kubernetes.proxy_url replaces the SSH tunnel; remove it or the [ssh] section");
+        }
+        Ok(())
+    }
+
+    /// Resolve the kubeconfig path relative to the config file, or as an
+    /// absolute / `~`-expanded path. `None` when no local one is configured.
+    pub fn kubeconfig_path(&self) -> Option<PathBuf> {
+        let raw = self.kubernetes.kubeconfig.as_ref()?;
+        if let Some(stripped) = raw.strip_prefix("~/")
+            && let Some(home) = dirs::home_dir()
+        {
+            return Some(home.join(stripped));
         }
         let path = PathBuf::from(raw);
         if path.is_absolute() {
-            path
+            Some(path)
         } else {
-            PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(path)
+            Some(self.dir.join(path))
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(content: &str) -> ConfigFile {
+        ConfigFile::parse(PathBuf::from("/etc/prism/config.toml"), content).unwrap()
+    }
+
+    const PROFILES: &str = r#"
+default_cluster = "prod"
+
+[ssh]
+host = "bastion"
+user = "ops"
+port = 2222
+
+[kubernetes]
+kubeconfig = "kube/config"
+context = "shared"
+
+[clusters.prod]
+kubernetes.context = "prod"
+
+[clusters.staging]
+ssh.host = "staging-bastion"
+kubernetes.remote_kubeconfig = "/etc/rancher/k3s/k3s.yaml"
+
+[clusters.lab]
+kubernetes.proxy_url = "socks5://127.0.0.1:1080"
+
+[clusters.direct]
+ssh = false
+"#;
+
+    #[test]
+    fn default_profile_is_applied_over_the_shared_settings() {
+        let config = parse(PROFILES).cluster(None).unwrap();
+        assert_eq!(config.name.as_deref(), Some("prod"));
+        assert_eq!(config.dir, PathBuf::from("/etc/prism"));
+        assert_eq!(config.kubernetes.context.as_deref(), Some("prod"));
+        assert_eq!(config.kubernetes.kubeconfig.as_deref(), Some("kube/config"));
+        assert_eq!(config.ssh.unwrap().host, "bastion");
+    }
+
+    #[test]
+    fn profile_overrides_a_nested_key_and_keeps_its_siblings() {
+        let config = parse(PROFILES).cluster(Some("staging")).unwrap();
+        let ssh = config.ssh.unwrap();
+        assert_eq!(ssh.host, "staging-bastion");
+        assert_eq!(ssh.user.as_deref(), Some("ops"));
+        assert_eq!(ssh.port, Some(2222));
+        assert_eq!(config.kubernetes.context.as_deref(), Some("shared"));
+    }
+
+    #[test]
+    fn profile_setting_an_alternative_drops_the_shared_one() {
+        let file = parse(PROFILES);
+
+        // remote_kubeconfig replaces the shared kubeconfig
+        let staging = file.cluster(Some("staging")).unwrap();
+        assert_eq!(staging.kubernetes.kubeconfig, None);
+        assert_eq!(
+            staging.kubernetes.remote_kubeconfig.as_deref(),
+            Some("/etc/rancher/k3s/k3s.yaml")
+        );
+
+        // proxy_url replaces the shared [ssh]
+        let lab = file.cluster(Some("lab")).unwrap();
+        assert!(lab.ssh.is_none());
+        assert_eq!(
+            lab.kubernetes.proxy_url.as_deref(),
+            Some("socks5://127.0.0.1:1080")
+        );
+        assert_eq!(lab.kubernetes.kubeconfig.as_deref(), Some("kube/config"));
+
+        // `ssh = false` removes [ssh] without touching anything else
+        let direct = file.cluster(Some("direct")).unwrap();
+        assert!(direct.ssh.is_none());
+        assert_eq!(direct.kubernetes.kubeconfig.as_deref(), Some("kube/config"));
+    }
+
+    #[test]
+    fn shared_alternative_is_dropped_the_other_way_round() {
+        let file = parse(
+            r#"
+[kubernetes]
+proxy_url = "http://proxy:3128"
+remote_kubeconfig = "/etc/kubernetes/admin.conf"
+
+[clusters.tunnel]
+ssh.host = "bastion"
+
+[clusters.local]
+kubernetes.kubeconfig = "kube/local"
+"#,
+        );
+        let tunnel = file.cluster(Some("tunnel")).unwrap();
+        assert_eq!(tunnel.kubernetes.proxy_url, None);
+        assert_eq!(tunnel.ssh.unwrap().host, "bastion");
+
+        // the shared proxy_url stays, the remote kubeconfig is replaced
+        let local = file.cluster(Some("local")).unwrap();
+        assert_eq!(local.kubernetes.remote_kubeconfig, None);
+        assert_eq!(local.kubernetes.kubeconfig.as_deref(), Some("kube/local"));
+    }
+
+    #[test]
+    fn unknown_cluster_lists_the_configured_ones() {
+        let error = parse(PROFILES).cluster(Some("dev")).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "No cluster named dev; configured: prod, staging, lab, direct"
+        );
+    }
+}
@@ -1,56 +1,123 @@
+use crate::history::MetricsHistory;
 use crate::k8s;
 
+const MI: i64 = 1024 * 1024;
+
 /// Dummy data to be used in --demo mode
 pub fn get_pods() -> Vec<k8s::PodInfo> {
     vec![
         k8s::PodInfo {
             name: "nginx-7b8d6c5d9-x4k2m".into(),
+            uid: "demo-nginx-7b8d6c5d9-x4k2m".into(),
+            cluster: None,
             namespace: "default".into(),
             status: "Running".into(),
             restarts: 0,
             age: "2d".into(),
+            cpu_request: Some(100),
+            cpu_limit: Some(500),
+            mem_request: Some(128 * MI),
+            mem_limit: Some(256 * MI),
+            usage: Some(k8s::metrics::Usage {
+                cpu_millis: 42,
+                memory_bytes: 61 * MI,
+            }),
         },
         k8s::PodInfo {
             name: "redis-master-0".into(),
+            uid: "demo-redis-master-0".into(),
+            cluster: None,
             namespace: "default".into(),
             status: "Running".into(),
             restarts: 1,
             age: "5d".into(),
+            cpu_request: Some(250),
+            cpu_limit: None,
+            mem_request: Some(256 * MI),
+            mem_limit: Some(512 * MI),
+            usage: Some(k8s::metrics::Usage {
+                cpu_millis: 310,
+                memory_bytes: 402 * MI,
+            }),
         },
         k8s::PodInfo {
             name: "api-gateway-6f7d8c9-q8n3p".into(),
+            uid: "demo-api-gateway-6f7d8c9-q8n3p".into(),
+            cluster: None,
             namespace: "backend".into(),
             status: "Running".into(),
             restarts: 0,
             age: "12h".into(),
+            cpu_request: Some(500),
+            cpu_limit: Some(1000),
+            mem_request: Some(512 * MI),
+            mem_limit: Some(MI * 1024),
+            usage: Some(k8s::metrics::Usage {
+                cpu_millis: 870,
+                memory_bytes: 498 * MI,
+            }),
         },
         k8s::PodInfo {
             name: "worker-batch-j7k2x".into(),
+            uid: "demo-worker-batch-j7k2x".into(),
+            cluster: None,
             namespace: "jobs".into(),
             status: "Succeeded".into(),
             restarts: 0,
             age: "3h".into(),
+            cpu_request: Some(200),
+            cpu_limit: Some(400),
+            mem_request: Some(256 * MI),
+            mem_limit: Some(256 * MI),
+            usage: None,
         },
         k8s::PodInfo {
             name: "postgres-0".into(),
+            uid: "demo-postgres-0".into(),
+            cluster: None,
             namespace: "database".into(),
             status: "Running".into(),
             restarts: 0,
             age: "14d".into(),
+            cpu_request: Some(1000),
+            cpu_limit: Some(2000),
+            mem_request: Some(MI * 1024),
+            mem_limit: Some(2048 * MI),
+            usage: Some(k8s::metrics::Usage {
+                cpu_millis: 240,
+                memory_bytes: 1210 * MI,
+            }),
         },
         k8s::PodInfo {
             name: "cronjob-cleanup-f9z1l".into(),
+            uid: "demo-cronjob-cleanup-f9z1l".into(),
+            cluster: None,
             namespace: "jobs".into(),
             status: "CrashLoopBackOff".into(),
             restarts: 12,
             age: "1h".into(),
+            cpu_request: Some(50),
+            cpu_limit: Some(100),
+            mem_request: Some(64 * MI),
+            mem_limit: Some(128 * MI),
+            usage: Some(k8s::metrics::Usage {
+                cpu_millis: 3,
+                memory_bytes: 9 * MI,
+            }),
         },
         k8s::PodInfo {
             name: "monitoring-agent-2v8x4".into(),
+            uid: "demo-monitoring-agent-2v8x4".into(),
+            cluster: None,
             namespace: "monitoring".into(),
             status: "Pending".into(),
             restarts: 0,
             age: "5m".into(),
+            cpu_request: None,
+            cpu_limit: None,
+            mem_request: None,
+            mem_limit: None,
+            usage: None,
         },
     ]
 }
@@ -59,38 +126,146 @@ pub fn get_deployments() -> Vec<k8s::DeploymentInfo> {
     vec![
         k8s::DeploymentInfo {
             name: "nginx".into(),
+            uid: "demo-nginx".into(),
+            cluster: None,
             namespace: "default".into(),
-            ready: "3/3".into(),
+            kind: k8s::WorkloadKind::Deployment,
+            ready: 3,
+            desired: 3,
             up_to_date: 3,
             age: "2d".into(),
         },
         k8s::DeploymentInfo {
             name: "api-gateway".into(),
+            uid: "demo-api-gateway".into(),
+            cluster: None,
             namespace: "backend".into(),
-            ready: "2/2".into(),
+            kind: k8s::WorkloadKind::Deployment,
+            ready: 2,
+            desired: 2,
             up_to_date: 2,
             age: "12h".into(),
         },
         k8s::DeploymentInfo {
             name: "redis".into(),
+            uid: "demo-redis".into(),
+            cluster: None,
             namespace: "default".into(),
-            ready: "1/1".into(),
+            kind: k8s::WorkloadKind::Deployment,
+            ready: 1,
+            desired: 1,
             up_to_date: 1,
             age: "5d".into(),
         },
         k8s::DeploymentInfo {
             name: "postgres".into(),
+            uid: "demo-postgres".into(),
+            cluster: None,
             namespace: "database".into(),
-            ready: "1/1".into(),
+            kind: k8s::WorkloadKind::StatefulSet,
+            ready: 1,
+            desired: 1,
             up_to_date: 1,
             age: "14d".into(),
         },
         k8s::DeploymentInfo {
             name: "monitoring-agent".into(),
+            uid: "demo-monitoring-agent".into(),
+            cluster: None,
             namespace: "monitoring".into(),
-            ready: "0/1".into(),
+            kind: k8s::WorkloadKind::Deployment,
+            ready: 0,
+            desired: 1,
             up_to_date: 0,
             age: "5m".into(),
         },
     ]
 }
+
+pub fn get_nodes() -> Vec<k8s::NodeInfo> {
+    vec![
+        k8s::NodeInfo {
+            name: "vps-control-1".into(),
+            cluster: None,
+            status: "Ready".into(),
+            roles: "control-plane,master".into(),
+            version: "v1.31.4+k3s1".into(),
+            age: "62d".into(),
+            cpu_allocatable: Some(4000),
+            mem_allocatable: Some(7820 * MI),
+            usage: Some(k8s::metrics::Usage {
+                cpu_millis: 1480,
+                memory_bytes: 3950 * MI,
+            }),
+        },
+        k8s::NodeInfo {
+            name: "vps-worker-1".into(),
+            cluster: None,
+            status: "Ready".into(),
+            roles: "<none>".into(),
+            version: "v1.31.4+k3s1".into(),
+            age: "41d".into(),
+            cpu_allocatable: Some(2000),
+            mem_allocatable: Some(3860 * MI),
+            usage: Some(k8s::metrics::Usage {
+                cpu_millis: 1710,
+                memory_bytes: 3420 * MI,
+            }),
+        },
+        k8s::NodeInfo {
+            name: "vps-worker-2".into(),
+            cluster: None,
+            status: "NotReady".into(),
+            roles: "<none>".into(),
+            version: "v1.31.4+k3s1".into(),
+            age: "41d".into(),
+            cpu_allocatable: Some(2000),
+            mem_allocatable: Some(3860 * MI),
+            usage: None,
+        },
+    ]
+}
+
+/// A full window of made-up samples wobbling around the current usage.
+pub fn get_history(pods: &[k8s::PodInfo], nodes: &[k8s::NodeInfo]) -> MetricsHistory {
+    let mut history = MetricsHistory::default();
+    let len = history.capacity();
+
+    for i in 0..len {
+        let wobble = |phase: usize, usage: Option<k8s::metrics::Usage>| {
+            let f = 1.0 + 0.3 * ((i + phase * 7) as f64 * 0.15).sin();
+            usage.map(|u| k8s::metrics::Usage {
+                cpu_millis: (u.cpu_millis as f64 * f) as i64,
+                memory_bytes: (u.memory_bytes as f64 * (0.9 + 0.1 * f)) as i64,
+            })
+        };
+        // restarts climb towards the current count, one every ~50s for crashing pods
+        let remaining = (len - 1 - i) as i32 / 10;
+
+        let pods: Vec<k8s::PodInfo> = pods
+            .iter()
+            .enumerate()
+            .map(|(n, p)| k8s::PodInfo {
+                usage: wobble(n, p.usage),
+                restarts: if p.restarts > 5 {
+                    (p.restarts - remaining).max(0)
+                } else {
+                    p.restarts
+                },
+                ..p.clone()
+            })
+            .collect();
+        let nodes: Vec<k8s::NodeInfo> = nodes
+            .iter()
+            .enumerate()
+            .map(|(n, node)| k8s::NodeInfo {
+                usage: wobble(n, node.usage),
+                ..node.clone()
+            })
+            .collect();
+
+        history.record(&pods, &nodes);
+    }
+
+    history
+}
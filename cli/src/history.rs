@@ -0,0 +1,127 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::time::Duration;
+
+use crate::k8s::{NodeInfo, PodInfo};
+
+/// Time between two samples; matches the dashboard poll interval.
+pub const SAMPLE_INTERVAL: Duration = Duration::from_secs(5);
+/// Window kept when the config sets none.
+const DEFAULT_WINDOW: Duration = Duration::from_secs(10 * 60);
+/// Restarts within the window after which a pod counts as flapping.
+pub const FLAPPING_RESTARTS: u64 = 3;
+
+/// Rolling samples of one pod or node, oldest first.
+///
+/// CPU and memory are `None` for polls where metrics-server had no data.
+#[derive(Clone, Debug, Default)]
+pub struct Series {
+    pub cpu: VecDeque<Option<u64>>,
+    pub memory: VecDeque<Option<u64>>,
+    pub restarts: VecDeque<u64>,
+}
+
+impl Series {
+    fn push(&mut self, len: usize, cpu: Option<u64>, memory: Option<u64>, restarts: u64) {
+        while self.cpu.len() >= len {
+            self.cpu.pop_front();
+            self.memory.pop_front();
+            self.restarts.pop_front();
+        }
+        self.cpu.push_back(cpu);
+        self.memory.push_back(memory);
+        self.restarts.push_back(restarts);
+    }
+
+    /// Restarts that happened within the window.
+    pub fn recent_restarts(&self) -> u64 {
+        match (self.restarts.front(), self.restarts.back()) {
+            (Some(first), Some(last)) => last.saturating_sub(*first),
+            _ => 0,
+        }
+    }
+
+    pub fn is_flapping(&self) -> bool {
+        self.recent_restarts() >= FLAPPING_RESTARTS
+    }
+
+    /// Time covered by the samples.
+    pub fn span(&self) -> Duration {
+        SAMPLE_INTERVAL * self.cpu.len().saturating_sub(1) as u32
+    }
+}
+
+/// In-memory metrics history of all pods and nodes seen by the dashboard.
+pub struct MetricsHistory {
+    /// Samples kept per resource.
+    len: usize,
+    pods: HashMap<(String, String), Series>,
+    nodes: HashMap<String, Series>,
+}
+
+impl Default for MetricsHistory {
+    fn default() -> Self {
+        Self::new(DEFAULT_WINDOW)
+    }
+}
+
+impl MetricsHistory {
+    /// Keep samples covering `window`, one every [`SAMPLE_INTERVAL`].
+    pub fn new(window: Duration) -> Self {
+        MetricsHistory {
+            len: (window.as_secs() / SAMPLE_INTERVAL.as_secs()).max(1) as usize,
+            pods: HashMap::new(),
+            nodes: HashMap::new(),
+        }
+    }
+
+    /// Samples kept per resource.
+    pub fn capacity(&self) -> usize {
+        self.len
+    }
+
+    /// Forget all samples, keeping the window.
+    pub fn clear(&mut self) {
+        self.pods.clear();
+        self.nodes.clear();
+    }
+
+    /// Append one sample per pod and node, forgetting resources that are gone.
+    pub fn record(&mut self, pods: &[PodInfo], nodes: &[NodeInfo]) {
+        let seen: HashSet<(&str, &str)> = pods
+            .iter()
+            .map(|p| (p.namespace.as_str(), p.name.as_str()))
+            .collect();
+        self.pods
+            .retain(|(ns, name), _| seen.contains(&(ns.as_str(), name.as_str())));
+        for pod in pods {
+            self.pods
+                .entry((pod.namespace.clone(), pod.name.clone()))
+                .or_default()
+                .push(
+                    self.len,
+                    pod.usage.map(|u| u.cpu_millis as u64),
+                    pod.usage.map(|u| u.memory_bytes as u64),
+                    pod.restarts.max(0) as u64,
+                );
+        }
+
+        let seen: HashSet<&str> = nodes.iter().map(|n| n.name.as_str()).collect();
+        self.nodes.retain(|name, _| seen.contains(name.as_str()));
+        for node in nodes {
+            self.nodes.entry(node.name.clone()).or_default().push(
+                self.len,
+                node.usage.map(|u| u.cpu_millis as u64),
+                node.usage.map(|u| u.memory_bytes as u64),
+                0,
+            );
+        }
+    }
+
+    pub fn pod(&self, namespace: &str, name: &str) -> Option<&Series> {
+        self.pods.get(&(namespace.to_string(), name.to_string()))
+    }
+
+    pub fn node(&self, name: &str) -> Option<&Series> {
+        self.nodes.get(name)
+    }
+}
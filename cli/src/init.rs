@@ -0,0 +1,322 @@
+use anyhow::{Context, Result, bail};
+use kube::config::Kubeconfig;
+use std::io::{self, IsTerminal, Write};
+use std::path::Path;
+use toml::{Table, Value};
+
+use crate::cluster::Cluster;
+use crate::config::{Config, ConfigFile};
+use crate::{k8s, ssh};
+
+/// Where clusters usually keep an admin kubeconfig, tried in order.
+const REMOTE_KUBECONFIGS: &[&str] = &[
+    "/etc/rancher/k3s/k3s.yaml",
+    "/etc/rancher/rke2/rke2.yaml",
+    "~/.kube/config",
+    "/etc/kubernetes/admin.conf",
+    "/var/snap/microk8s/current/credentials/client.config",
+];
+
+/// `prism init`: ask for the SSH host and the kubeconfig, try them out and
+/// write the config to `path`, or to [`ConfigFile::default_path`].
+pub async fn run(path: Option<&Path>) -> Result<()> {
+    if !io::stdin().is_terminal() {
+        bail!("prism init asks questions, run it in a terminal");
+    }
+    let path = match path {
+        Some(path) => path.to_path_buf(),
+        None => ConfigFile::default_path()?,
+    };
+    println!("Setting up {}\n", path.display());
+    if path.exists() && !confirm("It exists already. Overwrite it?", false)? {
+        println!("Left {} as it is", path.display());
+        return Ok(());
+    }
+
+    let mut table = Table::new();
+    let host = ask_host()?;
+    let (kubeconfig, kubernetes) = match host {
+        Some(host) => {
+            let (target, ssh) = ask_ssh(host)?;
+            table.insert("ssh".into(), Value::Table(ssh));
+            test_ssh(&target).await?;
+            fetch_kubeconfig(&target).await?
+        }
+        None => read_local_kubeconfig().await?,
+    };
+    table.insert("kubernetes".into(), Value::Table(kubernetes));
+
+    pick_context(&mut table, &kubeconfig)?;
+    let cfg = parse(&path, &table)?;
+
+    println!("\nProbing the API server...");
+    if let Err(e) = probe(&cfg).await {
+        println!("  ✗ {:#}", e);
+        if !confirm("Write the config anyway?", false)? {
+            bail!("Nothing written");
+        }
+    }
+
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create {}", dir.display()))?;
+    }
+    let content = format!(
+        "# Written by prism init, see config.example.toml for more settings\n\n{}",
+        toml::to_string(&table)?
+    );
+    std::fs::write(&path, content)
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    // a file prism would not find on its own needs --config
+    let command = match ConfigFile::find() {
+        Ok(found) if found == path => "prism".to_string(),
+        _ => format!("prism --config {}", path.display()),
+    };
+    println!(
+        "\nWrote {}, run {} to open the dashboard",
+        path.display(),
+        command
+    );
+    Ok(())
+}
+
+/// The SSH host to go through, `None` to reach the API server directly.
+fn ask_host() -> Result<Option<String>> {
+    let ssh_config = ssh::expand_home("~/.ssh/config");
+    let aliases = ssh::target::host_aliases(&ssh_config).unwrap_or_default();
+    if !aliases.is_empty() {
+        println!("Hosts in {}:", ssh_config.display());
+        for (i, alias) in aliases.iter().enumerate() {
+            println!("  {}) {}", i + 1, alias);
+        }
+    }
+    let answer = ask(
+        "SSH host: number, alias or hostname (empty to reach the API server directly)",
+        None,
+    )?;
+    Ok(match answer.parse::<usize>() {
+        Ok(n) if (1..=aliases.len()).contains(&n) => Some(aliases[n - 1].clone()),
+        _ if answer.is_empty() => None,
+        _ => Some(answer),
+    })
+}
+
+/// Ask for user, port and key, offering what the ssh config says for `host`.
+/// Only answers that differ from it end up in the `[ssh]` section.
+fn ask_ssh(host: String) -> Result<(ssh::Target, Table)> {
+    let mut table = Table::new();
+    table.insert("host".into(), Value::String(host));
+    let defaults = resolve(&table)?.host;
+
+    let user = ask("User", Some(&defaults.user))?;
+    if user != defaults.user {
+        table.insert("user".into(), Value::String(user));
+    }
+    let port = loop {
+        match ask("Port", Some(&defaults.port.to_string()))?.parse::<u16>() {
+            Ok(port) => break port,
+            Err(_) => println!("  Not a port number"),
+        }
+    };
+    if port != defaults.port {
+        table.insert("port".into(), Value::Integer(port.into()));
+    }
+    let default_key = defaults
+        .identity_files
+        .first()
+        .map(|key| key.display().to_string());
+    let key = match &default_key {
+        Some(key) => ask("Key file", Some(key))?,
+        None => ask("Key file (empty for ssh-agent only)", None)?,
+    };
+    if !key.is_empty() && Some(&key) != default_key.as_ref() {
+        table.insert("key_path".into(), Value::String(key));
+    }
+
+    Ok((resolve(&table)?, table))
+}
+
+fn resolve(ssh: &Table) -> Result<ssh::Target> {
+    let ssh = ssh.clone().try_into().context("Invalid SSH settings")?;
+    ssh::Target::resolve(&ssh).context("Failed to read SSH config")
+}
+
+async fn test_ssh(target: &ssh::Target) -> Result<()> {
+    println!("\nConnecting to {}...", target);
+    ssh::exec(target, "true", true)
+        .await
+        .context("SSH connection failed, check the answers above and run prism init again")?;
+    println!("  ✓ SSH connection works");
+    Ok(())
+}
+
+/// Look for a kubeconfig on the SSH host, let the user pick one and read it.
+/// It stays on the host, the config only names it.
+async fn fetch_kubeconfig(target: &ssh::Target) -> Result<(Kubeconfig, Table)> {
+    let mut command = String::new();
+    for (i, path) in REMOTE_KUBECONFIGS.iter().enumerate() {
+        let path = ssh::shell_path(path);
+        command.push_str(&format!(
+            "if [ -r {path} ]; then echo r {i}; elif [ -e {path} ]; then echo e {i}; fi; "
+        ));
+    }
+    let output = ssh::exec(target, &command, true)
+        .await
+        .context("Failed to look for a kubeconfig")?;
+
+    let mut readable = Vec::new();
+    for line in output.lines() {
+        let Some((kind, i)) = line.split_once(' ') else {
+            continue;
+        };
+        let Some(path) = i
+            .parse::<usize>()
+            .ok()
+            .and_then(|i| REMOTE_KUBECONFIGS.get(i))
+        else {
+            continue;
+        };
+        match kind {
+            "r" => readable.push(path.to_string()),
+            _ => println!("  {} exists but {} cannot read it", path, target.host.user),
+        }
+    }
+    if !readable.is_empty() {
+        println!("Kubeconfigs on {}:", target.host.hostname);
+        for (i, path) in readable.iter().enumerate() {
+            println!("  {}) {}", i + 1, path);
+        }
+    }
+
+    loop {
+        let answer = ask(
+            "Remote kubeconfig: number or path",
+            readable.first().map(String::as_str),
+        )?;
+        let path = match answer.parse::<usize>() {
+            Ok(n) if (1..=readable.len()).contains(&n) => readable[n - 1].clone(),
+            _ if answer.is_empty() => continue,
+            _ => answer,
+        };
+        let yaml = match ssh::read_remote_file(target, &path, true).await {
+            Ok(yaml) => yaml,
+            Err(e) => {
+                println!("  ✗ {:#}", e);
+                continue;
+            }
+        };
+        match Kubeconfig::from_yaml(&yaml) {
+            Ok(kubeconfig) => {
+                println!("  ✓ Read {}", path);
+                let mut table = Table::new();
+                table.insert("remote_kubeconfig".into(), Value::String(path));
+                return Ok((kubeconfig, table));
+            }
+            Err(e) => println!("  ✗ {} is not a kubeconfig: {}", path, e),
+        }
+    }
+}
+
+/// Read a kubeconfig on this machine, `$KUBECONFIG` or `~/.kube/config`
+/// unless another one is named.
+async fn read_local_kubeconfig() -> Result<(Kubeconfig, Table)> {
+    loop {
+        let mut path = ask("Kubeconfig (empty for $KUBECONFIG or ~/.kube/config)", None)?;
+        // the config resolves relative paths against its own directory
+        if !path.is_empty() && !path.starts_with("~/") && Path::new(&path).is_relative() {
+            path = std::path::absolute(&path)?.display().to_string();
+        }
+        let mut table = Table::new();
+        if !path.is_empty() {
+            table.insert("kubeconfig".into(), Value::String(path));
+        }
+        let mut wrapper = Table::new();
+        wrapper.insert("kubernetes".into(), Value::Table(table.clone()));
+        let cfg: Config = wrapper.try_into().context("Invalid kubeconfig path")?;
+        match k8s::load_kubeconfig(&cfg, None, false).await {
+            Ok(kubeconfig) => {
+                println!("  ✓ Read the kubeconfig");
+                return Ok((kubeconfig, table));
+            }
+            Err(e) => println!("  ✗ {:#}", e),
+        }
+    }
+}
+
+/// Offer the contexts of the kubeconfig when there are several; only a
+/// context other than the current one is written.
+fn pick_context(table: &mut Table, kubeconfig: &Kubeconfig) -> Result<()> {
+    let names = k8s::context_names(kubeconfig);
+    if names.len() < 2 {
+        return Ok(());
+    }
+    println!("Contexts:");
+    for (i, name) in names.iter().enumerate() {
+        println!("  {}) {}", i + 1, name);
+    }
+    let current = kubeconfig.current_context.clone();
+    let context = loop {
+        let answer = ask("Context: number or name", current.as_deref())?;
+        match answer.parse::<usize>() {
+            Ok(n) if (1..=names.len()).contains(&n) => break names[n - 1].clone(),
+            _ if names.contains(&answer) => break answer,
+            _ => println!("  No such context"),
+        }
+    };
+    if Some(&context) != current.as_ref() {
+        section(table, "kubernetes").insert("context".into(), Value::String(context));
+    }
+    Ok(())
+}
+
+/// Validate `table` the way prism reads it from `path`.
+fn parse(path: &Path, table: &Table) -> Result<Config> {
+    let content = toml::to_string(table)?;
+    ConfigFile::parse(path.to_path_buf(), &content)?.cluster(None)
+}
+
+/// Connect like the dashboard does and ask the API server for its version.
+async fn probe(cfg: &Config) -> Result<()> {
+    let cluster = Cluster::connect(cfg, true, |step| println!("  {}", step)).await?;
+    let version = cluster.client.apiserver_version().await;
+    cluster.close().await;
+    println!("  ✓ API server {}", version?.git_version);
+    Ok(())
+}
+
+fn section<'a>(table: &'a mut Table, key: &str) -> &'a mut Table {
+    match table
+        .entry(key)
+        .or_insert_with(|| Value::Table(Table::new()))
+    {
+        Value::Table(section) => section,
+        _ => unreachable!("{} is always a table", key),
+    }
+}
+
+/// Ask on the terminal; an empty answer gives `default`.
+fn ask(question: &str, default: Option<&str>) -> Result<String> {
+    match default {
+        Some(default) => print!("{} [{}]: ", question, default),
+        None => print!("{}: ", question),
+    }
+    io::stdout().flush().ok();
+    let mut answer = String::new();
+    if io::stdin().read_line(&mut answer)? == 0 {
+        bail!("Setup cancelled");
+    }
+    let answer = answer.trim();
+    Ok(match (answer.is_empty(), default) {
+        (true, Some(default)) => default.to_string(),
+        _ => answer.to_string(),
+    })
+}
+
+fn confirm(question: &str, default: bool) -> Result<bool> {
+    let hint = if default { "Y/n" } else { "y/N" };
+    let answer = ask(&format!("{} [{}]", question, hint), None)?;
+    Ok(match answer.to_ascii_lowercase().as_str() {
+        "" => default,
+        answer => matches!(answer, "y" | "yes"),
+    })
+}
@@ -0,0 +1,232 @@
+use std::collections::HashMap;
+
+use anyhow::{Context, Result};
+use kube::api::{ApiResource, DynamicObject, ListParams};
+use kube::{Api, Client};
+use serde::Deserialize;
+
+/// CPU and memory consumption as reported by metrics-server.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Usage {
+    pub cpu_millis: i64,
+    pub memory_bytes: i64,
+}
+
+#[derive(Deserialize)]
+struct ContainerMetrics {
+    usage: HashMap<String, String>,
+}
+
+#[derive(Deserialize)]
+struct PodMetricsData {
+    containers: Vec<ContainerMetrics>,
+}
+
+#[derive(Deserialize)]
+struct NodeMetricsData {
+    usage: HashMap<String, String>,
+}
+
+fn metrics_resource(kind: &str, plural: &str) -> ApiResource {
+    ApiResource {
+        group: "metrics.k8s.io".into(),
+        version: "v1beta1".into(),
+        api_version: "metrics.k8s.io/v1beta1".into(),
+        kind: kind.into(),
+        plural: plural.into(),
+    }
+}
+
+/// Fetch usage of the pods of `namespace`, or of all pods, keyed by
+/// `(namespace, name)`.
+///
+/// Returns `Ok(None)` when the metrics API is not served, i.e. metrics-server
+/// is not installed or not ready yet, or the user may not read it.
+pub async fn fetch_pod_metrics(
+    client: &Client,
+    namespace: Option<&str>,
+) -> Result<Option<HashMap<(String, String), Usage>>> {
+    let resource = metrics_resource("PodMetrics", "pods");
+    let api: Api<DynamicObject> = match namespace {
+        Some(ns) => Api::namespaced_with(client.clone(), ns, &resource),
+        None => Api::all_with(client.clone(), &resource),
+    };
+    let Some(list) = list_metrics(&api).await? else {
+        return Ok(None);
+    };
+
+    let usage = list
+        .into_iter()
+        .filter_map(|obj| {
+            let data: PodMetricsData = serde_json::from_value(obj.data).ok()?;
+            let total = data
+                .containers
+                .iter()
+                .map(|c| usage_from_map(&c.usage))
+                .fold(Usage::default(), |acc, u| Usage {
+                    cpu_millis: acc.cpu_millis + u.cpu_millis,
+                    memory_bytes: acc.memory_bytes + u.memory_bytes,
+                });
+            Some((
+                (
+                    obj.metadata.namespace.unwrap_or_default(),
+                    obj.metadata.name.unwrap_or_default(),
+                ),
+                total,
+            ))
+        })
+        .collect();
+
+    Ok(Some(usage))
+}
+
+/// Fetch usage of all nodes keyed by name. `Ok(None)` as for pods.
+pub async fn fetch_node_metrics(client: &Client) -> Result<Option<HashMap<String, Usage>>> {
+    let api: Api<DynamicObject> =
+        Api::all_with(client.clone(), &metrics_resource("NodeMetrics", "nodes"));
+    let Some(list) = list_metrics(&api).await? else {
+        return Ok(None);
+    };
+
+    let usage = list
+        .into_iter()
+        .filter_map(|obj| {
+            let data: NodeMetricsData = serde_json::from_value(obj.data).ok()?;
+            Some((
+                obj.metadata.name.unwrap_or_default(),
+                usage_from_map(&data.usage),
+            ))
+        })
+        .collect();
+
+    Ok(Some(usage))
+}
+
+/// List metrics objects, mapping "API not served" and "forbidden" responses
+/// to `None`.
+async fn list_metrics(api: &Api<DynamicObject>) -> Result<Option<Vec<DynamicObject>>> {
+    match api.list(&ListParams::default()).await {
+        Ok(list) => Ok(Some(list.items)),
+        Err(kube::Error::Api(status)) if matches!(status.code, 403 | 404 | 503) => Ok(None),
+        Err(e) => Err(e).context("Failed to query metrics.k8s.io"),
+    }
+}
+
+fn usage_from_map(usage: &HashMap<String, String>) -> Usage {
+    Usage {
+        cpu_millis: usage.get("cpu").and_then(|q| parse_cpu(q)).unwrap_or(0),
+        memory_bytes: usage
+            .get("memory")
+            .and_then(|q| parse_memory(q))
+            .unwrap_or(0),
+    }
+}
+
+/// Parse a CPU quantity ("250m", "1", "1.5", "12345n") into millicores.
+pub fn parse_cpu(q: &str) -> Option<i64> {
+    let (num, scale) = if let Some(n) = q.strip_suffix('n') {
+        (n, 1e-6)
+    } else if let Some(n) = q.strip_suffix('u') {
+        (n, 1e-3)
+    } else if let Some(n) = q.strip_suffix('m') {
+        (n, 1.0)
+    } else {
+        (q, 1000.0)
+    };
+    num.parse::<f64>().ok().map(|v| (v * scale).round() as i64)
+}
+
+/// Parse a memory quantity ("128Mi", "1G", "1e9", "512", "1500m") into bytes.
+pub fn parse_memory(q: &str) -> Option<i64> {
+    const SUFFIXES: [(&str, f64); 13] = [
+        ("Ki", 1024.0),
+        ("Mi", 1024.0 * 1024.0),
+        ("Gi", 1024.0 * 1024.0 * 1024.0),
+        ("Ti", 1024.0 * 1024.0 * 1024.0 * 1024.0),
+        ("Pi", 1024.0 * 1024.0 * 1024.0 * 1024.0 * 1024.0),
+        ("Ei", 1024.0 * 1024.0 * 1024.0 * 1024.0 * 1024.0 * 1024.0),
+        ("k", 1e3),
+        ("M", 1e6),
+        ("G", 1e9),
+        ("T", 1e12),
+        ("P", 1e15),
+        ("E", 1e18),
+        ("m", 1e-3),
+    ];
+
+    for (suffix, scale) in SUFFIXES {
+        if let Some(n) = q.strip_suffix(suffix) {
+            return n.parse::<f64>().ok().map(|v| (v * scale).round() as i64);
+        }
+    }
+    // plain bytes, possibly in exponent notation
+    q.parse::<f64>().ok().map(|v| v as i64)
+}
+
+/// Format millicores like kubectl top: "250m", or cores above 10.
+pub fn format_cpu(millis: i64) -> String {
+    if millis >= 10_000 {
+        format!("{:.1}", millis as f64 / 1000.0)
+    } else {
+        format!("{}m", millis)
+    }
+}
+
+/// Format bytes in binary units: "512Ki", "128Mi", "1.5Gi".
+pub fn format_memory(bytes: i64) -> String {
+    const MI: f64 = 1024.0 * 1024.0;
+    let b = bytes as f64;
+    if b >= 1024.0 * MI {
+        format!("{:.1}Gi", b / (1024.0 * MI))
+    } else if b >= MI {
+        format!("{:.0}Mi", b / MI)
+    } else {
+        format!("{:.0}Ki", b / 1024.0)
+    }
+}
+
+/// Usage as a whole percentage of `total`, if there is a total.
+pub fn percent(used: i64, total: Option<i64>) -> Option<i64> {
+    match total {
+        Some(t) if t > 0 => Some(used * 100 / t),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_cpu_scales_each_suffix_to_millicores() {
+        assert_eq!(parse_cpu("12345678n"), Some(12));
+        assert_eq!(parse_cpu("2500u"), Some(3));
+        assert_eq!(parse_cpu("250m"), Some(250));
+        assert_eq!(parse_cpu("1"), Some(1000));
+        assert_eq!(parse_cpu("1.5"), Some(1500));
+        assert_eq!(parse_cpu("0"), Some(0));
+    }
+
+    #[test]
+    fn parse_memory_scales_binary_and_decimal_suffixes_to_bytes() {
+        assert_eq!(parse_memory("512Ki"), Some(512 * 1024));
+        assert_eq!(parse_memory("128Mi"), Some(128 * 1024 * 1024));
+        assert_eq!(parse_memory("1.5Gi"), Some(3 * 512 * 1024 * 1024));
+        assert_eq!(parse_memory("2k"), Some(2_000));
+        assert_eq!(parse_memory("1M"), Some(1_000_000));
+        // "m" is milli, not mega
+        assert_eq!(parse_memory("1500m"), Some(2));
+        assert_eq!(parse_memory("1e9"), Some(1_000_000_000));
+        assert_eq!(parse_memory("512"), Some(512));
+    }
+
+    #[test]
+    fn parse_rejects_malformed_quantities() {
+        for q in ["", "abc", "m", "Mi", "1.2.3", "10x", "-"] {
+            assert_eq!(parse_cpu(q), None, "cpu {:?}", q);
+            assert_eq!(parse_memory(q), None, "memory {:?}", q);
+        }
+        assert_eq!(parse_cpu("1Gi"), None);
+        assert_eq!(parse_memory("1Xi"), None);
+    }
+}
@@ -0,0 +1,861 @@
+use anyhow::{Context, Result, bail};
+use hyper_util::rt::TokioExecutor;
+use k8s_openapi::api::apps::v1::{Deployment, StatefulSet};
+use k8s_openapi::api::core::v1::{Container, Node, Pod};
+use kube::api::{DeleteParams, ListParams, LogParams, Patch, PatchParams};
+use kube::client::ConfigExt;
+use kube::config::{Config, KubeConfigOptions, Kubeconfig};
+use kube::{Api, Client};
+use std::ffi::OsStr;
+use std::path::PathBuf;
+use std::time::SystemTime;
+use tower::{BoxError, ServiceBuilder};
+
+use crate::config::Config as PrismConfig;
+use crate::ssh::{self, Endpoint, SshConnector, Target};
+
+pub mod metrics;
+pub mod rollout;
+
+use metrics::Usage;
+
+/// Pod struct
+#[derive(Clone, Debug)]
+pub struct PodInfo {
+    pub name: String,
+    /// `metadata.uid`, tells a recreated pod from the deleted one.
+    pub uid: String,
+    /// Profile the row was listed from, only set in the all-clusters view.
+    pub cluster: Option<String>,
+    pub namespace: String,
+    pub status: String,
+    pub restarts: i32,
+    pub age: String,
+    pub cpu_request: Option<i64>,
+    pub cpu_limit: Option<i64>,
+    pub mem_request: Option<i64>,
+    pub mem_limit: Option<i64>,
+    /// Filled in from metrics-server when available.
+    pub usage: Option<Usage>,
+}
+
+/// Node struct
+#[derive(Clone, Debug)]
+pub struct NodeInfo {
+    pub name: String,
+    /// Profile the row was listed from, only set in the all-clusters view.
+    pub cluster: Option<String>,
+    pub status: String,
+    pub roles: String,
+    pub version: String,
+    pub age: String,
+    pub cpu_allocatable: Option<i64>,
+    pub mem_allocatable: Option<i64>,
+    /// Filled in from metrics-server when available.
+    pub usage: Option<Usage>,
+}
+
+/// Kind of a scalable workload shown in the deployments panel.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WorkloadKind {
+    Deployment,
+    StatefulSet,
+}
+
+impl WorkloadKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            WorkloadKind::Deployment => "Deployment",
+            WorkloadKind::StatefulSet => "StatefulSet",
+        }
+    }
+}
+
+/// Kind of any resource prism can act on.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ResourceKind {
+    Pod,
+    Deployment,
+    StatefulSet,
+}
+
+impl ResourceKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ResourceKind::Pod => "Pod",
+            ResourceKind::Deployment => "Deployment",
+            ResourceKind::StatefulSet => "StatefulSet",
+        }
+    }
+}
+
+impl From<WorkloadKind> for ResourceKind {
+    fn from(kind: WorkloadKind) -> Self {
+        match kind {
+            WorkloadKind::Deployment => ResourceKind::Deployment,
+            WorkloadKind::StatefulSet => ResourceKind::StatefulSet,
+        }
+    }
+}
+
+/// Identifies a single namespaced resource.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ResourceRef {
+    pub kind: ResourceKind,
+    pub namespace: String,
+    pub name: String,
+    /// `metadata.uid` of the object the ref was taken from, empty if unknown.
+    pub uid: String,
+}
+
+impl std::fmt::Display for ResourceRef {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}/{}", self.kind.as_str(), self.namespace, self.name)
+    }
+}
+
+/// Deployment struct
+#[derive(Clone, Debug)]
+pub struct DeploymentInfo {
+    pub name: String,
+    /// `metadata.uid`, tells a recreated workload from the deleted one.
+    pub uid: String,
+    /// Profile the row was listed from, only set in the all-clusters view.
+    pub cluster: Option<String>,
+    pub namespace: String,
+    pub kind: WorkloadKind,
+    pub ready: i32,
+    pub desired: i32,
+    pub up_to_date: i32,
+    pub age: String,
+}
+
+/// Build a kube client that connects through the ssh tunnel.
+pub async fn build_client(
+    config: &PrismConfig,
+    kubeconfig: Kubeconfig,
+    endpoint: &Endpoint,
+) -> Result<Client> {
+    let mut kube_config = kube_config(config, kubeconfig).await?;
+
+    // the certificate is checked against the kubeconfig CA under the API
+    // server's own name, not the tunnel address
+    if let Some(name) = &config.kubernetes.tls_server_name {
+        kube_config.tls_server_name = Some(name.clone());
+    }
+
+    match endpoint {
+        Endpoint::Local(port) => {
+            // Route through the ssh tunnel.
+            if kube_config.tls_server_name.is_none() {
+                kube_config.tls_server_name = kube_config.cluster_url.host().map(String::from);
+            }
+            let tunnel_url = format!("https://127.0.0.1:{}", port);
+            kube_config.cluster_url = tunnel_url.parse().context("Invalid tunnel URL")?;
+
+            Client::try_from(kube_config).context("Failed to create Kubernetes client")
+        }
+        Endpoint::Socks(port) => {
+            // names are resolved on the SSH host
+            let proxy_url = format!("socks5://127.0.0.1:{}", port);
+            kube_config.proxy_url = Some(proxy_url.parse().context("Invalid proxy URL")?);
+            Client::try_from(kube_config).context("Failed to create Kubernetes client")
+        }
+        Endpoint::Channel(connector) => channel_client(kube_config, connector.clone()),
+        Endpoint::Direct => {
+            if let Some(proxy_url) = &config.kubernetes.proxy_url {
+                kube_config.proxy_url = Some(
+                    proxy_url
+                        .parse()
+                        .with_context(|| format!("Invalid proxy_url {}", proxy_url))?,
+                );
+            }
+            Client::try_from(kube_config).context("Failed to create Kubernetes client")
+        }
+    }
+}
+
+/// Read the kubeconfig from disk or, with `remote_kubeconfig`, from the SSH
+/// host. A remote kubeconfig is parsed in memory and never written to disk.
+/// Without either, the files in `$KUBECONFIG` are merged like kubectl does,
+/// falling back to `~/.kube/config`.
+pub async fn load_kubeconfig(
+    config: &PrismConfig,
+    target: Option<&Target>,
+    interactive: bool,
+) -> Result<Kubeconfig> {
+    if let Some(path) = &config.kubernetes.remote_kubeconfig {
+        let target = target.context("kubernetes.remote_kubeconfig needs an [ssh] section")?;
+        let yaml = ssh::read_remote_file(target, path, interactive).await?;
+        return Kubeconfig::from_yaml(&yaml).with_context(|| {
+            format!(
+                "Failed to parse kubeconfig {} from {}",
+                path, target.host.hostname
+            )
+        });
+    }
+
+    if let Some(kubeconfig_path) = config.kubeconfig_path() {
+        return Kubeconfig::read_from(&kubeconfig_path).with_context(|| {
+            format!("Failed to read kubeconfig at {}", kubeconfig_path.display())
+        });
+    }
+
+    // none configured, look where kubectl does
+    let paths = kubeconfig_paths(std::env::var_os("KUBECONFIG").as_deref());
+    if !paths.is_empty() {
+        return merge_kubeconfigs(&paths);
+    }
+    let kubeconfig_path = dirs::home_dir()
+        .context("No kubeconfig configured and no home directory to look in")?
+        .join(".kube")
+        .join("config");
+    Kubeconfig::read_from(&kubeconfig_path).with_context(|| {
+        format!(
+            "No kubeconfig configured and failed to read {}",
+            kubeconfig_path.display()
+        )
+    })
+}
+
+/// Files listed in a `$KUBECONFIG` value; empty entries are ignored, so an
+/// empty variable lists none.
+fn kubeconfig_paths(value: Option<&OsStr>) -> Vec<PathBuf> {
+    value
+        .map(|value| {
+            std::env::split_paths(value)
+                .filter(|path| !path.as_os_str().is_empty())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Merge the kubeconfig files of `$KUBECONFIG`; files that do not exist are
+/// skipped, as kubectl does.
+fn merge_kubeconfigs(paths: &[PathBuf]) -> Result<Kubeconfig> {
+    let existing: Vec<&PathBuf> = paths.iter().filter(|path| path.exists()).collect();
+    if existing.is_empty() {
+        bail!(
+            "None of the kubeconfig files in KUBECONFIG exist: {}",
+            std::env::join_paths(paths)
+                .unwrap_or_default()
+                .to_string_lossy()
+        );
+    }
+    let mut merged = Kubeconfig::default();
+    for path in existing {
+        let kubeconfig = Kubeconfig::read_from(path)
+            .with_context(|| format!("Failed to read kubeconfig at {}", path.display()))?;
+        merged = merged
+            .merge(kubeconfig)
+            .with_context(|| format!("Failed to merge kubeconfig {}", path.display()))?;
+    }
+    Ok(merged)
+}
+
+/// kube config for the configured context, or the kubeconfig's current one.
+async fn kube_config(config: &PrismConfig, kubeconfig: Kubeconfig) -> Result<Config> {
+    let context = config.kubernetes.context.clone();
+    if let Some(name) = &context
+        && !kubeconfig.contexts.iter().any(|c| &c.name == name)
+    {
+        bail!(
+            "No context {} in the kubeconfig; available: {}",
+            name,
+            context_names(&kubeconfig).join(", ")
+        );
+    }
+    let options = KubeConfigOptions {
+        context,
+        ..Default::default()
+    };
+    Config::from_custom_kubeconfig(kubeconfig, &options)
+        .await
+        .context("Failed to build kube config from kubeconfig file")
+}
+
+/// Names of the contexts in the kubeconfig, in file order.
+pub fn context_names(kubeconfig: &Kubeconfig) -> Vec<String> {
+    kubeconfig.contexts.iter().map(|c| c.name.clone()).collect()
+}
+
+/// Name of the context in use and its default namespace, if it sets one.
+pub fn selected_context(
+    config: &PrismConfig,
+    kubeconfig: &Kubeconfig,
+) -> (Option<String>, Option<String>) {
+    let name = config
+        .kubernetes
+        .context
+        .clone()
+        .or_else(|| kubeconfig.current_context.clone());
+    let namespace = name.as_ref().and_then(|name| {
+        kubeconfig
+            .contexts
+            .iter()
+            .find(|c| &c.name == name)
+            .and_then(|c| c.context.as_ref()?.namespace.clone())
+    });
+    (name, namespace)
+}
+
+/// Host of the API server URL of the context in use, without IPv6 brackets.
+pub async fn server_host(config: &PrismConfig, kubeconfig: &Kubeconfig) -> Result<String> {
+    let kube_config = kube_config(config, kubeconfig.clone()).await?;
+    kube_config
+        .cluster_url
+        .host()
+        .map(|host| host.trim_matches(['[', ']']).to_string())
+        .context("The kubeconfig server URL has no host")
+}
+
+/// Port of the API server URL of the context in use.
+pub async fn server_port(config: &PrismConfig, kubeconfig: &Kubeconfig) -> Result<u16> {
+    let kube_config = kube_config(config, kubeconfig.clone()).await?;
+    Ok(kube_config.cluster_url.port_u16().unwrap_or(443))
+}
+
+/// Client whose connections are SSH channels. The cluster URL stays as in the
+/// kubeconfig; it only determines the Host header and TLS server name.
+fn channel_client(config: Config, connector: SshConnector) -> Result<Client> {
+    let https = config
+        .rustls_https_connector_with_connector(connector)
+        .context("Failed to set up TLS for the API server")?;
+    let http: hyper_util::client::legacy::Client<_, kube::client::Body> =
+        hyper_util::client::legacy::Client::builder(TokioExecutor::new()).build(https);
+
+    let service = ServiceBuilder::new()
+        .layer(config.base_uri_layer())
+        .option_layer(
+            config
+                .auth_layer()
+                .context("Failed to set up API authentication")?,
+        )
+        .layer(
+            config
+                .extra_headers_layer()
+                .context("Invalid extra headers in kubeconfig")?,
+        )
+        .map_err(BoxError::from)
+        .service(http);
+
+    Ok(Client::new(service, config.default_namespace))
+}
+
+/// Get the current Unix epoch in seconds.
+fn now_epoch_secs() -> i64 {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs() as i64
+}
+
+/// Compute a human-readable age string from a k8s-openapi jiff Timestamp.
+fn age_from_timestamp(ts: &k8s_openapi::apimachinery::pkg::apis::meta::v1::Time) -> String {
+    let created = ts.0.as_second();
+    let elapsed = now_epoch_secs() - created;
+    format_duration(elapsed)
+}
+
+/// An `Api` over `namespace`, or over all namespaces when it is `None`.
+/// Users bound to one namespace may not list cluster-wide.
+fn scoped<K>(client: &Client, namespace: Option<&str>) -> Api<K>
+where
+    K: kube::Resource<Scope = k8s_openapi::NamespaceResourceScope>,
+    K::DynamicType: Default,
+{
+    match namespace {
+        Some(ns) => Api::namespaced(client.clone(), ns),
+        None => Api::all(client.clone()),
+    }
+}
+
+/// Fetch the pods of `namespace`, or across all namespaces.
+pub async fn fetch_pods(client: &Client, namespace: Option<&str>) -> Result<Vec<PodInfo>> {
+    let pods: Api<Pod> = scoped(client, namespace);
+    let list = pods
+        .list(&ListParams::default())
+        .await
+        .context("Failed to list pods")?;
+
+    let infos = list
+        .items
+        .into_iter()
+        .map(|pod| {
+            let meta = &pod.metadata;
+            let status = pod.status.as_ref();
+
+            // kubectl reports pods with a deletion timestamp as Terminating
+            let phase = if meta.deletion_timestamp.is_some() {
+                "Terminating".into()
+            } else {
+                status
+                    .and_then(|s| s.phase.clone())
+                    .unwrap_or_else(|| "Unknown".into())
+            };
+
+            let restarts = status
+                .and_then(|s| s.container_statuses.as_ref())
+                .map(|cs| cs.iter().map(|c| c.restart_count).sum())
+                .unwrap_or(0);
+
+            let age = meta
+                .creation_timestamp
+                .as_ref()
+                .map(age_from_timestamp)
+                .unwrap_or_else(|| "-".into());
+
+            let containers = pod
+                .spec
+                .as_ref()
+                .map(|s| s.containers.as_slice())
+                .unwrap_or_default();
+
+            PodInfo {
+                name: meta.name.clone().unwrap_or_default(),
+                uid: meta.uid.clone().unwrap_or_default(),
+                cluster: None,
+                namespace: meta.namespace.clone().unwrap_or_else(|| "default".into()),
+                status: phase,
+                restarts,
+                age,
+                cpu_request: container_total(containers, false, "cpu", metrics::parse_cpu),
+                cpu_limit: container_total(containers, true, "cpu", metrics::parse_cpu),
+                mem_request: container_total(containers, false, "memory", metrics::parse_memory),
+                mem_limit: container_total(containers, true, "memory", metrics::parse_memory),
+                usage: None,
+            }
+        })
+        .collect();
+
+    Ok(infos)
+}
+
+/// Sum a resource request or limit over all containers, `None` if any
+/// container leaves it unset.
+fn container_total(
+    containers: &[Container],
+    limits: bool,
+    resource: &str,
+    parse: fn(&str) -> Option<i64>,
+) -> Option<i64> {
+    if containers.is_empty() {
+        return None;
+    }
+    containers
+        .iter()
+        .map(|c| {
+            let reqs = c.resources.as_ref()?;
+            let map = if limits { &reqs.limits } else { &reqs.requests };
+            map.as_ref()?.get(resource).and_then(|q| parse(&q.0))
+        })
+        .sum()
+}
+
+/// Fetch all nodes. `Ok(None)` when the user may not list them, as users
+/// bound to a namespace usually may not.
+pub async fn fetch_nodes(client: &Client) -> Result<Option<Vec<NodeInfo>>> {
+    let nodes: Api<Node> = Api::all(client.clone());
+    let list = match nodes.list(&ListParams::default()).await {
+        Ok(list) => list,
+        Err(kube::Error::Api(status)) if status.code == 403 => return Ok(None),
+        Err(e) => return Err(e).context("Failed to list nodes"),
+    };
+
+    let infos = list
+        .items
+        .into_iter()
+        .map(|node| {
+            let meta = &node.metadata;
+            let status = node.status.as_ref();
+
+            let ready = status
+                .and_then(|s| s.conditions.as_ref())
+                .and_then(|cs| cs.iter().find(|c| c.type_ == "Ready"))
+                .map(|c| c.status == "True")
+                .unwrap_or(false);
+            let unschedulable = node
+                .spec
+                .as_ref()
+                .and_then(|s| s.unschedulable)
+                .unwrap_or(false);
+            let mut state = if ready { "Ready" } else { "NotReady" }.to_string();
+            if unschedulable {
+                state.push_str(",SchedulingDisabled");
+            }
+
+            let mut roles: Vec<&str> = meta
+                .labels
+                .iter()
+                .flatten()
+                .filter_map(|(k, _)| k.strip_prefix("node-role.kubernetes.io/"))
+                .collect();
+            roles.sort();
+
+            let allocatable = status.and_then(|s| s.allocatable.as_ref());
+
+            NodeInfo {
+                name: meta.name.clone().unwrap_or_default(),
+                cluster: None,
+                status: state,
+                roles: if roles.is_empty() {
+                    "<none>".into()
+                } else {
+                    roles.join(",")
+                },
+                version: status
+                    .and_then(|s| s.node_info.as_ref())
+                    .map(|i| i.kubelet_version.clone())
+                    .unwrap_or_default(),
+                age: meta
+                    .creation_timestamp
+                    .as_ref()
+                    .map(age_from_timestamp)
+                    .unwrap_or_else(|| "-".into()),
+                cpu_allocatable: allocatable
+                    .and_then(|a| a.get("cpu"))
+                    .and_then(|q| metrics::parse_cpu(&q.0)),
+                mem_allocatable: allocatable
+                    .and_then(|a| a.get("memory"))
+                    .and_then(|q| metrics::parse_memory(&q.0)),
+                usage: None,
+            }
+        })
+        .collect();
+
+    Ok(Some(infos))
+}
+
+/// Fetch the deployments of `namespace`, or across all namespaces.
+pub async fn fetch_deployments(
+    client: &Client,
+    namespace: Option<&str>,
+) -> Result<Vec<DeploymentInfo>> {
+    let deploys: Api<Deployment> = scoped(client, namespace);
+    let list = deploys
+        .list(&ListParams::default())
+        .await
+        .context("Failed to list deployments")?;
+
+    let infos = list
+        .items
+        .into_iter()
+        .map(|dep| {
+            let meta = &dep.metadata;
+            let status = dep.status.as_ref();
+
+            let ready = status.and_then(|s| s.ready_replicas).unwrap_or(0);
+            let desired = dep.spec.as_ref().and_then(|s| s.replicas).unwrap_or(0);
+            let up_to_date = status.and_then(|s| s.updated_replicas).unwrap_or(0);
+
+            let age = meta
+                .creation_timestamp
+                .as_ref()
+                .map(age_from_timestamp)
+                .unwrap_or_else(|| "-".into());
+
+            DeploymentInfo {
+                name: meta.name.clone().unwrap_or_default(),
+                uid: meta.uid.clone().unwrap_or_default(),
+                cluster: None,
+                namespace: meta.namespace.clone().unwrap_or_else(|| "default".into()),
+                kind: WorkloadKind::Deployment,
+                ready,
+                desired,
+                up_to_date,
+                age,
+            }
+        })
+        .collect();
+
+    Ok(infos)
+}
+
+/// Fetch the statefulsets of `namespace`, or across all namespaces.
+pub async fn fetch_statefulsets(
+    client: &Client,
+    namespace: Option<&str>,
+) -> Result<Vec<DeploymentInfo>> {
+    let sets: Api<StatefulSet> = scoped(client, namespace);
+    let list = sets
+        .list(&ListParams::default())
+        .await
+        .context("Failed to list statefulsets")?;
+
+    let infos = list
+        .items
+        .into_iter()
+        .map(|sts| {
+            let meta = &sts.metadata;
+            let status = sts.status.as_ref();
+
+            let ready = status.and_then(|s| s.ready_replicas).unwrap_or(0);
+            let desired = sts.spec.as_ref().and_then(|s| s.replicas).unwrap_or(0);
+            let up_to_date = status.and_then(|s| s.updated_replicas).unwrap_or(0);
+
+            let age = meta
+                .creation_timestamp
+                .as_ref()
+                .map(age_from_timestamp)
+                .unwrap_or_else(|| "-".into());
+
+            DeploymentInfo {
+                name: meta.name.clone().unwrap_or_default(),
+                uid: meta.uid.clone().unwrap_or_default(),
+                cluster: None,
+                namespace: meta.namespace.clone().unwrap_or_else(|| "default".into()),
+                kind: WorkloadKind::StatefulSet,
+                ready,
+                desired,
+                up_to_date,
+                age,
+            }
+        })
+        .collect();
+
+    Ok(infos)
+}
+
+/// Set the desired replica count through the workload's scale subresource.
+pub async fn scale_workload(
+    client: &Client,
+    kind: WorkloadKind,
+    namespace: &str,
+    name: &str,
+    replicas: i32,
+) -> Result<()> {
+    let patch = serde_json::json!({ "spec": { "replicas": replicas } });
+    let params = PatchParams::default();
+
+    match kind {
+        WorkloadKind::Deployment => {
+            let api: Api<Deployment> = Api::namespaced(client.clone(), namespace);
+            api.patch_scale(name, &params, &Patch::Merge(&patch)).await
+        }
+        WorkloadKind::StatefulSet => {
+            let api: Api<StatefulSet> = Api::namespaced(client.clone(), namespace);
+            api.patch_scale(name, &params, &Patch::Merge(&patch)).await
+        }
+    }
+    .with_context(|| format!("Failed to scale {}/{}", namespace, name))?;
+
+    Ok(())
+}
+
+/// Delete a resource. `grace_period` overrides the resource's default,
+/// `force` deletes immediately without waiting for graceful termination.
+pub async fn delete_resource(
+    client: &Client,
+    resource: &ResourceRef,
+    grace_period: Option<u32>,
+    force: bool,
+) -> Result<()> {
+    let mut params = DeleteParams::background();
+    if force {
+        params = params.grace_period(0);
+    } else if let Some(secs) = grace_period {
+        params = params.grace_period(secs);
+    }
+
+    let (ns, name) = (resource.namespace.as_str(), resource.name.as_str());
+    match resource.kind {
+        ResourceKind::Pod => Api::<Pod>::namespaced(client.clone(), ns)
+            .delete(name, &params)
+            .await
+            .map(|_| ()),
+        ResourceKind::Deployment => Api::<Deployment>::namespaced(client.clone(), ns)
+            .delete(name, &params)
+            .await
+            .map(|_| ()),
+        ResourceKind::StatefulSet => Api::<StatefulSet>::namespaced(client.clone(), ns)
+            .delete(name, &params)
+            .await
+            .map(|_| ()),
+    }
+    .with_context(|| format!("Failed to delete {}", resource))
+}
+
+/// Set a label on a resource, or remove it when `value` is `None`.
+pub async fn label_resource(
+    client: &Client,
+    resource: &ResourceRef,
+    key: &str,
+    value: Option<&str>,
+) -> Result<()> {
+    let patch = serde_json::json!({ "metadata": { "labels": { key: value } } });
+    let params = PatchParams::default();
+
+    let (ns, name) = (resource.namespace.as_str(), resource.name.as_str());
+    match resource.kind {
+        ResourceKind::Pod => Api::<Pod>::namespaced(client.clone(), ns)
+            .patch(name, &params, &Patch::Merge(&patch))
+            .await
+            .map(|_| ()),
+        ResourceKind::Deployment => Api::<Deployment>::namespaced(client.clone(), ns)
+            .patch(name, &params, &Patch::Merge(&patch))
+            .await
+            .map(|_| ()),
+        ResourceKind::StatefulSet => Api::<StatefulSet>::namespaced(client.clone(), ns)
+            .patch(name, &params, &Patch::Merge(&patch))
+            .await
+            .map(|_| ()),
+    }
+    .with_context(|| format!("Failed to label {}", resource))
+}
+
+/// Container names of a pod, the one `kubectl logs` picks by default first:
+/// the `kubectl.kubernetes.io/default-container` annotation, else the first.
+pub async fn pod_containers(client: &Client, namespace: &str, name: &str) -> Result<Vec<String>> {
+    let pods: Api<Pod> = Api::namespaced(client.clone(), namespace);
+    let pod = pods
+        .get(name)
+        .await
+        .with_context(|| format!("Failed to get pod {}/{}", namespace, name))?;
+
+    let mut names: Vec<String> = pod
+        .spec
+        .map(|s| s.containers.into_iter().map(|c| c.name).collect())
+        .unwrap_or_default();
+    let default = pod
+        .metadata
+        .annotations
+        .and_then(|a| a.get("kubectl.kubernetes.io/default-container").cloned());
+    if let Some(i) = default.and_then(|d| names.iter().position(|n| *n == d)) {
+        let name = names.remove(i);
+        names.insert(0, name);
+    }
+    Ok(names)
+}
+
+/// Fetch the last `lines` log lines of a pod's `container`, or of its only
+/// container.
+pub async fn tail_logs(
+    client: &Client,
+    namespace: &str,
+    name: &str,
+    container: Option<&str>,
+    lines: i64,
+) -> Result<String> {
+    let pods: Api<Pod> = Api::namespaced(client.clone(), namespace);
+    let params = LogParams {
+        container: container.map(str::to_string),
+        tail_lines: Some(lines),
+        ..LogParams::default()
+    };
+    pods.logs(name, &params)
+        .await
+        .with_context(|| format!("Failed to fetch logs for {}/{}", namespace, name))
+}
+
+/// Format elapsed seconds into a human-friendly string like "2d", "5h", "13m".
+/// Could be moved to utils module?
+fn format_duration(secs: i64) -> String {
+    let secs = secs.max(0);
+    if secs < 60 {
+        format!("{}s", secs)
+    } else if secs < 3600 {
+        format!("{}m", secs / 60)
+    } else if secs < 86400 {
+        format!("{}h", secs / 3600)
+    } else {
+        format!("{}d", secs / 86400)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Write `files` into a fresh directory under the temp dir.
+    fn temp_dir(name: &str, files: &[(&str, &str)]) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("prism-k8s-{}-{}", std::process::id(), name));
+        std::fs::remove_dir_all(&dir).ok();
+        std::fs::create_dir_all(&dir).unwrap();
+        for (file, content) in files {
+            std::fs::write(dir.join(file), content).unwrap();
+        }
+        dir
+    }
+
+    /// Kubeconfig with one context `context` on the cluster `cluster`.
+    fn kubeconfig(context: &str, cluster: &str, server: &str) -> String {
+        format!(
+            "apiVersion: v1
+kind: Config
+current-context: {context}
+clusters:
+- name: {cluster}
+  cluster:
+    server: {server}
+contexts:
+- name: {context}
+  context:
+    cluster: {cluster}
+    user: {context}
+users:
+- name: {context}
+  user:
+    token: {context}
+"
+        )
+    }
+
+    fn server(kubeconfig: &Kubeconfig, cluster: &str) -> Option<String> {
+        kubeconfig
+            .clusters
+            .iter()
+            .find(|c| c.name == cluster)
+            .and_then(|c| c.cluster.as_ref()?.server.clone())
+    }
+
+    #[test]
+    fn merge_skips_missing_files() {
+        let dir = temp_dir(
+            "missing",
+            &[("a.yaml", &kubeconfig("a", "a", "https://a:6443"))],
+        );
+        let merged =
+            merge_kubeconfigs(&[dir.join("gone.yaml"), dir.join("a.yaml"), dir.join("nope")])
+                .unwrap();
+        assert_eq!(context_names(&merged), ["a"]);
+        assert_eq!(merged.current_context.as_deref(), Some("a"));
+
+        let error = merge_kubeconfigs(&[dir.join("gone.yaml")]).unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .starts_with("None of the kubeconfig files")
+        );
+    }
+
+    #[test]
+    fn merge_keeps_the_first_file_that_sets_a_value() {
+        let dir = temp_dir(
+            "order",
+            &[
+                ("a.yaml", &kubeconfig("a", "shared", "https://a:6443")),
+                ("b.yaml", &kubeconfig("b", "shared", "https://b:6443")),
+            ],
+        );
+        let merged = merge_kubeconfigs(&[dir.join("a.yaml"), dir.join("b.yaml")]).unwrap();
+        assert_eq!(context_names(&merged), ["a", "b"]);
+        assert_eq!(merged.current_context.as_deref(), Some("a"));
+        assert_eq!(merged.clusters.len(), 1);
+        assert_eq!(server(&merged, "shared").as_deref(), Some("https://a:6443"));
+
+        let merged = merge_kubeconfigs(&[dir.join("b.yaml"), dir.join("a.yaml")]).unwrap();
+        assert_eq!(merged.current_context.as_deref(), Some("b"));
+        assert_eq!(server(&merged, "shared").as_deref(), Some("https://b:6443"));
+    }
+
+    #[test]
+    fn empty_kubeconfig_variable_lists_no_files() {
+        assert!(kubeconfig_paths(None).is_empty());
+        assert!(kubeconfig_paths(Some(OsStr::new(""))).is_empty());
+        assert!(kubeconfig_paths(Some(OsStr::new("::"))).is_empty());
+        assert_eq!(
+            kubeconfig_paths(Some(OsStr::new("a.yaml::/b.yaml"))),
+            [PathBuf::from("a.yaml"), PathBuf::from("/b.yaml")]
+        );
+    }
+}
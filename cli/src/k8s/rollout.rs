@@ -0,0 +1,343 @@
+use anyhow::{Context, Result};
+use k8s_openapi::api::apps::v1::{Deployment, ReplicaSet, StatefulSet};
+use k8s_openapi::api::core::v1::PodTemplateSpec;
+use kube::api::{ListParams, Patch, PatchParams, PostParams};
+use kube::{Api, Client};
+use similar::{ChangeTag, TextDiff};
+
+use super::{ResourceKind, ResourceRef, age_from_timestamp};
+
+const REVISION_ANNOTATION: &str = "deployment.kubernetes.io/revision";
+const CHANGE_CAUSE_ANNOTATION: &str = "kubernetes.io/change-cause";
+const RESTARTED_AT_ANNOTATION: &str = "kubectl.kubernetes.io/restartedAt";
+
+/// One entry of a deployment's rollout history, backed by an owned ReplicaSet.
+#[derive(Clone, Debug)]
+pub struct Revision {
+    pub revision: i64,
+    pub replica_set: String,
+    pub change_cause: Option<String>,
+    pub images: Vec<String>,
+    pub replicas: i32,
+    pub ready: i32,
+    pub age: String,
+    pub template: Option<PodTemplateSpec>,
+}
+
+/// Kind of a line in a revision diff.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DiffTag {
+    Hunk,
+    Added,
+    Removed,
+    Context,
+}
+
+#[derive(Clone, Debug)]
+pub struct DiffLine {
+    pub tag: DiffTag,
+    pub text: String,
+}
+
+/// Snapshot of a deployment rollout, modelled on `kubectl rollout status`.
+#[derive(Clone, Debug)]
+pub struct RolloutStatus {
+    pub desired: i32,
+    pub updated: i32,
+    pub ready: i32,
+    pub available: i32,
+    pub message: String,
+    pub done: bool,
+    pub failed: bool,
+}
+
+/// Restart a workload by bumping the pod template's restartedAt annotation.
+pub async fn restart(client: &Client, resource: &ResourceRef) -> Result<()> {
+    let patch = serde_json::json!({
+        "spec": { "template": { "metadata": { "annotations": {
+            RESTARTED_AT_ANNOTATION: chrono::Utc::now().to_rfc3339(),
+        }}}}
+    });
+    let params = PatchParams::default();
+
+    let (ns, name) = (resource.namespace.as_str(), resource.name.as_str());
+    match resource.kind {
+        ResourceKind::Pod => anyhow::bail!("{} cannot be restarted, delete it instead", resource),
+        ResourceKind::Deployment => {
+            let api: Api<Deployment> = Api::namespaced(client.clone(), ns);
+            api.patch(name, &params, &Patch::Merge(&patch))
+                .await
+                .map(|_| ())
+        }
+        ResourceKind::StatefulSet => {
+            let api: Api<StatefulSet> = Api::namespaced(client.clone(), ns);
+            api.patch(name, &params, &Patch::Merge(&patch))
+                .await
+                .map(|_| ())
+        }
+    }
+    .with_context(|| format!("Failed to restart {}", resource))
+}
+
+/// Compute the current rollout status of a deployment.
+pub async fn status(client: &Client, namespace: &str, name: &str) -> Result<RolloutStatus> {
+    let api: Api<Deployment> = Api::namespaced(client.clone(), namespace);
+    let dep = api
+        .get(name)
+        .await
+        .with_context(|| format!("Failed to get deployment {}/{}", namespace, name))?;
+
+    let generation = dep.metadata.generation.unwrap_or(0);
+    let desired = dep.spec.as_ref().and_then(|s| s.replicas).unwrap_or(1);
+    let status = dep.status.unwrap_or_default();
+    let replicas = status.replicas.unwrap_or(0);
+    let updated = status.updated_replicas.unwrap_or(0);
+    let ready = status.ready_replicas.unwrap_or(0);
+    let available = status.available_replicas.unwrap_or(0);
+
+    let deadline_exceeded = status.conditions.iter().flatten().any(|c| {
+        c.type_ == "Progressing" && c.reason.as_deref() == Some("ProgressDeadlineExceeded")
+    });
+
+    let (message, done, failed) = if status.observed_generation.unwrap_or(0) < generation {
+        (
+            "Waiting for deployment spec update to be observed...".into(),
+            false,
+            false,
+        )
+    } else if deadline_exceeded {
+        (
+            format!(
+                "Deployment {:?} exceeded its progress deadline (ProgressDeadlineExceeded)",
+                name
+            ),
+            false,
+            true,
+        )
+    } else if updated < desired {
+        (
+            format!(
+                "Waiting for rollout to finish: {} out of {} new replicas have been updated...",
+                updated, desired
+            ),
+            false,
+            false,
+        )
+    } else if replicas > updated {
+        (
+            format!(
+                "Waiting for rollout to finish: {} old replicas are pending termination...",
+                replicas - updated
+            ),
+            false,
+            false,
+        )
+    } else if available < updated {
+        (
+            format!(
+                "Waiting for rollout to finish: {} of {} updated replicas are available...",
+                available, updated
+            ),
+            false,
+            false,
+        )
+    } else {
+        (
+            format!("Deployment {:?} successfully rolled out", name),
+            true,
+            false,
+        )
+    };
+
+    Ok(RolloutStatus {
+        desired,
+        updated,
+        ready,
+        available,
+        message,
+        done,
+        failed,
+    })
+}
+
+/// List the revision history of a deployment, newest first.
+pub async fn history(client: &Client, namespace: &str, name: &str) -> Result<Vec<Revision>> {
+    let api: Api<Deployment> = Api::namespaced(client.clone(), namespace);
+    let dep = api
+        .get(name)
+        .await
+        .with_context(|| format!("Failed to get deployment {}/{}", namespace, name))?;
+
+    let mut revisions: Vec<Revision> = owned_replica_sets(client, &dep)
+        .await?
+        .into_iter()
+        .filter_map(|rs| {
+            let meta = &rs.metadata;
+            let revision = meta
+                .annotations
+                .as_ref()
+                .and_then(|a| a.get(REVISION_ANNOTATION))
+                .and_then(|r| r.parse().ok())?;
+
+            let images = rs
+                .spec
+                .as_ref()
+                .and_then(|s| s.template.as_ref())
+                .and_then(|t| t.spec.as_ref())
+                .map(|s| {
+                    s.containers
+                        .iter()
+                        .filter_map(|c| c.image.clone())
+                        .collect()
+                })
+                .unwrap_or_default();
+
+            Some(Revision {
+                revision,
+                replica_set: meta.name.clone().unwrap_or_default(),
+                change_cause: meta
+                    .annotations
+                    .as_ref()
+                    .and_then(|a| a.get(CHANGE_CAUSE_ANNOTATION))
+                    .cloned(),
+                images,
+                replicas: rs.status.as_ref().map(|s| s.replicas).unwrap_or(0),
+                ready: rs
+                    .status
+                    .as_ref()
+                    .and_then(|s| s.ready_replicas)
+                    .unwrap_or(0),
+                age: meta
+                    .creation_timestamp
+                    .as_ref()
+                    .map(age_from_timestamp)
+                    .unwrap_or_else(|| "-".into()),
+                template: rs.spec.as_ref().and_then(|s| s.template.clone()),
+            })
+        })
+        .collect();
+
+    revisions.sort_by_key(|r| std::cmp::Reverse(r.revision));
+    Ok(revisions)
+}
+
+/// Roll a deployment back to the pod template of the given revision.
+pub async fn undo(client: &Client, namespace: &str, name: &str, revision: i64) -> Result<()> {
+    let api: Api<Deployment> = Api::namespaced(client.clone(), namespace);
+    let mut dep = api
+        .get(name)
+        .await
+        .with_context(|| format!("Failed to get deployment {}/{}", namespace, name))?;
+
+    let rs = owned_replica_sets(client, &dep)
+        .await?
+        .into_iter()
+        .find(|rs| {
+            rs.metadata
+                .annotations
+                .as_ref()
+                .and_then(|a| a.get(REVISION_ANNOTATION))
+                .and_then(|r| r.parse::<i64>().ok())
+                == Some(revision)
+        })
+        .with_context(|| format!("Revision {} not found for {}/{}", revision, namespace, name))?;
+
+    let mut template = rs
+        .spec
+        .and_then(|s| s.template)
+        .context("ReplicaSet has no pod template")?;
+
+    // must not leak back into the deployment
+    strip_template_hash(&mut template);
+
+    let spec = dep.spec.as_mut().context("Deployment has no spec")?;
+    spec.template = template;
+
+    api.replace(name, &PostParams::default(), &dep)
+        .await
+        .with_context(|| {
+            format!(
+                "Failed to roll back {}/{} to revision {}",
+                namespace, name, revision
+            )
+        })?;
+
+    Ok(())
+}
+
+/// Unified diff of the pod templates of two revisions.
+pub fn diff_revisions(old: &Revision, new: &Revision) -> Vec<DiffLine> {
+    let old_yaml = template_yaml(old);
+    let new_yaml = template_yaml(new);
+    let diff = TextDiff::from_lines(&old_yaml, &new_yaml);
+
+    let mut lines = Vec::new();
+    for hunk in diff.unified_diff().context_radius(3).iter_hunks() {
+        lines.push(DiffLine {
+            tag: DiffTag::Hunk,
+            text: hunk.header().to_string(),
+        });
+        for change in hunk.iter_changes() {
+            let tag = match change.tag() {
+                ChangeTag::Delete => DiffTag::Removed,
+                ChangeTag::Insert => DiffTag::Added,
+                ChangeTag::Equal => DiffTag::Context,
+            };
+            lines.push(DiffLine {
+                tag,
+                text: change.value().trim_end_matches('\n').to_string(),
+            });
+        }
+    }
+    lines
+}
+
+fn template_yaml(rev: &Revision) -> String {
+    let mut template = rev.template.clone().unwrap_or_default();
+    strip_template_hash(&mut template);
+    serde_yaml::to_string(&template).unwrap_or_default()
+}
+
+/// Drop the label the controller adds to each ReplicaSet's template.
+fn strip_template_hash(template: &mut PodTemplateSpec) {
+    if let Some(labels) = template.metadata.as_mut().and_then(|m| m.labels.as_mut()) {
+        labels.remove("pod-template-hash");
+    }
+}
+
+/// ReplicaSets controlled by the given deployment.
+async fn owned_replica_sets(client: &Client, dep: &Deployment) -> Result<Vec<ReplicaSet>> {
+    let namespace = dep.metadata.namespace.as_deref().unwrap_or("default");
+    let uid = dep.metadata.uid.as_deref().unwrap_or_default();
+
+    let selector = dep
+        .spec
+        .as_ref()
+        .and_then(|s| s.selector.match_labels.as_ref())
+        .map(|labels| {
+            labels
+                .iter()
+                .map(|(k, v)| format!("{}={}", k, v))
+                .collect::<Vec<_>>()
+                .join(",")
+        })
+        .unwrap_or_default();
+
+    let sets: Api<ReplicaSet> = Api::namespaced(client.clone(), namespace);
+    let list = sets
+        .list(&ListParams::default().labels(&selector))
+        .await
+        .context("Failed to list replicasets")?;
+
+    Ok(list
+        .items
+        .into_iter()
+        .filter(|rs| {
+            rs.metadata
+                .owner_references
+                .iter()
+                .flatten()
+                .any(|o| o.controller == Some(true) && o.uid == uid)
+        })
+        .collect())
+}
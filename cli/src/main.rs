@@ -1,24 +1,71 @@
 mod app;
+mod check;
+mod cluster;
 mod config;
 mod dummy;
+mod history;
+mod init;
 mod k8s;
 mod ssh;
 mod ui;
 
 use anyhow::{Context, Result};
-use clap::Parser;
+use clap::{Parser, Subcommand};
+use std::path::PathBuf;
 
 #[derive(Parser)]
 #[command(name = "prism", about = "K8s cluster monitor over SSH")]
 struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
     #[arg(long)]
     demo: bool,
+    /// Config file, instead of $PRISM_CONFIG or ~/.config/prism/config.toml
+    #[arg(long, value_name = "PATH", global = true)]
+    config: Option<PathBuf>,
+    /// Cluster profile from config.toml, instead of the default one
+    #[arg(long, short, global = true)]
+    cluster: Option<String>,
+    /// Show every cluster profile at once
+    #[arg(long, conflicts_with = "cluster")]
+    all_clusters: bool,
+    /// Kubeconfig context, instead of the one in config.toml or the kubeconfig
+    #[arg(long, conflicts_with = "all_clusters")]
+    context: Option<String>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Set up a config file step by step
+    Init,
+    /// Work with the config file
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommand,
+    },
+}
+
+#[derive(Subcommand)]
+enum ConfigCommand {
+    /// Validate the config file and the files it points to
+    Check {
+        /// Also connect to each cluster over SSH and to its API server
+        #[arg(long)]
+        connect: bool,
+    },
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
     let cli = Cli::parse();
 
+    match cli.command {
+        Some(Command::Init) => return init::run(cli.config.as_deref()).await,
+        Some(Command::Config {
+            command: ConfigCommand::Check { connect },
+        }) => return check::run(cli.config.as_deref(), cli.cluster.as_deref(), connect).await,
+        None => {}
+    }
     if cli.demo {
         return run_demo().await;
     }
@@ -26,35 +73,31 @@ async fn main() -> Result<()> {
     eprintln!("{}", ui::theme::BANNER);
 
     // load config
-    let cfg = config::Config::load().context(
-        "Could not load config. Create cli/config.toml — see config.example.toml for format.",
+    let config = config::ConfigFile::load(cli.config.as_deref()).context(
+        "Could not load config. Run prism init, or see config.example.toml for the format.",
     )?;
+    let mut app = app::App::new();
+    app.history = history::MetricsHistory::new(std::time::Duration::from_secs(
+        config.dashboard.history_minutes * 60,
+    ));
+    if cli.all_clusters {
+        if config.cluster_names().is_empty() {
+            anyhow::bail!("--all-clusters needs [clusters] profiles in config.toml");
+        }
+        app.switch(Some(app::Scope::All), None);
+    } else {
+        // fail on an unknown --cluster before the dashboard takes the terminal
+        let cfg = config.cluster(cli.cluster.as_deref())?;
+        app.switch(cfg.name.map(app::Scope::Cluster), cli.context);
+    }
 
-    // connect ssh tunnel
-    eprintln!(
-        "  Connecting to {}@{}:{}...",
-        cfg.ssh.user, cfg.ssh.host, cfg.ssh.port
-    );
-    let tunnel =
-        ssh::SshTunnel::establish(&cfg.ssh, cfg.kubernetes.local_port, cfg.kubernetes.api_port)
-            .await
-            .context("Failed to establish SSH tunnel")?;
-    eprintln!("  Tunnel ready on localhost:{}", cfg.kubernetes.local_port);
-
-    // build k8s client
-    let client = k8s::build_client(&cfg)
-        .await
-        .context("Failed to connect to Kubernetes API")?;
-    eprintln!("  Connected to cluster. Launching dashboard...\n");
-
-    // enter tui after connection is up
+    // connect from the dashboard, progress and host keys are shown there
+    eprintln!("  Launching dashboard...\n");
     let mut terminal = ratatui::init();
-    let mut app = app::App::new();
-    let result = app.run(&mut terminal, &client).await;
+    let result = app.run(&mut terminal, &config).await;
 
-    // restore & clean up
+    // restore
     ratatui::restore();
-    tunnel.close().await.ok();
 
     result
 }
@@ -70,6 +113,9 @@ async fn run_demo() -> Result<()> {
     let mut app = app::App::new();
     app.pods = dummy::get_pods();
     app.deployments = dummy::get_deployments();
+    app.nodes = dummy::get_nodes();
+    app.metrics_available = Some(true);
+    app.history = dummy::get_history(&app.pods, &app.nodes);
 
     let result = app.run_demo(&mut terminal).await;
     ratatui::restore();
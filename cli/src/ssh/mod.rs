@@ -0,0 +1,158 @@
+use anyhow::{Context, Result};
+use std::fmt;
+use std::path::PathBuf;
+
+use crate::config::SshTransport;
+
+pub mod native;
+pub mod openssh;
+pub mod supervisor;
+pub mod target;
+
+pub use native::SshConnector;
+pub use supervisor::{ConnectionState, Supervisor};
+pub use target::Target;
+
+/// Where the kube client sends its API connections.
+#[derive(Clone)]
+pub enum Endpoint {
+    /// Port forwarded by the ssh binary on 127.0.0.1.
+    Local(u16),
+    /// SOCKS5 proxy on 127.0.0.1 run by the ssh binary (`ssh -D`).
+    Socks(u16),
+    /// Fresh SSH channel per connection, no local port involved.
+    Channel(SshConnector),
+    /// No tunnel, the kubeconfig server URL is used as is.
+    Direct,
+}
+
+/// Address of the API server as seen from the SSH host.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Forward {
+    pub host: String,
+    pub port: u16,
+}
+
+impl fmt::Display for Forward {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.host.contains(':') {
+            write!(f, "[{}]:{}", self.host, self.port)
+        } else {
+            write!(f, "{}:{}", self.host, self.port)
+        }
+    }
+}
+
+/// An SSH connection to the cluster host, through either transport.
+pub enum SshTunnel {
+    Native(native::Session),
+    OpenSsh(openssh::Tunnel),
+}
+
+impl SshTunnel {
+    /// Connect to the SSH host so that the API server at `forward` becomes
+    /// reachable. Without `forward`, connections go wherever the client asks,
+    /// through a SOCKS proxy for the openssh transport. `local_port` is only
+    /// bound by the openssh transport, which picks a free port when it is
+    /// `None`.
+    /// With `interactive`, unknown host keys can be confirmed on the terminal;
+    /// only before the dashboard runs.
+    pub async fn establish(
+        target: &Target,
+        local_port: Option<u16>,
+        forward: Option<&Forward>,
+        interactive: bool,
+    ) -> Result<Self> {
+        match target.ssh.transport {
+            SshTransport::Native => native::Session::connect(target, forward, interactive)
+                .await
+                .map(SshTunnel::Native),
+            SshTransport::OpenSsh => {
+                openssh::Tunnel::establish(target, local_port, forward, interactive)
+                    .await
+                    .map(SshTunnel::OpenSsh)
+            }
+        }
+    }
+
+    pub fn endpoint(&self) -> Endpoint {
+        match self {
+            SshTunnel::Native(session) => Endpoint::Channel(session.connector()),
+            SshTunnel::OpenSsh(tunnel) if tunnel.is_socks() => Endpoint::Socks(tunnel.local_port()),
+            SshTunnel::OpenSsh(tunnel) => Endpoint::Local(tunnel.local_port()),
+        }
+    }
+
+    /// Why the tunnel is down, or `None` while it is healthy.
+    pub async fn lost(&mut self) -> Option<String> {
+        match self {
+            SshTunnel::Native(session) => session
+                .is_closed()
+                .then(|| "SSH connection closed".to_string()),
+            SshTunnel::OpenSsh(tunnel) => {
+                if tunnel.is_alive() {
+                    None
+                } else {
+                    Some(tunnel.exit_reason().await)
+                }
+            }
+        }
+    }
+
+    /// Bring a lost tunnel back up on the same endpoint.
+    pub async fn reconnect(&mut self, target: &Target, forward: Option<&Forward>) -> Result<()> {
+        match self {
+            SshTunnel::Native(session) => session.reconnect(target).await,
+            SshTunnel::OpenSsh(tunnel) => {
+                let local_port = Some(tunnel.local_port());
+                *tunnel = openssh::Tunnel::establish(target, local_port, forward, false).await?;
+                Ok(())
+            }
+        }
+    }
+
+    /// Shut down the tunnel.
+    pub async fn close(self) -> Result<()> {
+        match self {
+            SshTunnel::Native(session) => session.close().await,
+            SshTunnel::OpenSsh(tunnel) => tunnel.close().await,
+        }
+    }
+}
+
+/// Run `command` on the SSH host over a connection of its own and return
+/// its output.
+pub async fn exec(target: &Target, command: &str, interactive: bool) -> Result<String> {
+    match target.ssh.transport {
+        SshTransport::Native => native::exec(target, command, interactive).await,
+        SshTransport::OpenSsh => openssh::exec(target, command, interactive).await,
+    }
+}
+
+/// Read a file on the SSH host into memory, over a connection of its own.
+pub async fn read_remote_file(target: &Target, path: &str, interactive: bool) -> Result<String> {
+    let command = format!("cat -- {}", shell_path(path));
+    exec(target, &command, interactive)
+        .await
+        .with_context(|| format!("Failed to read {} on {}", path, target.host.hostname))
+}
+
+/// `path` quoted for the remote shell. A leading `~/` stays unquoted so the
+/// shell expands it.
+pub fn shell_path(path: &str) -> String {
+    let quote = |s: &str| format!("'{}'", s.replace('\'', r"'\''"));
+    match path.strip_prefix("~/") {
+        Some(rest) => format!("~/{}", quote(rest)),
+        None => quote(path),
+    }
+}
+
+/// Expand a leading `~/` to the home directory.
+pub fn expand_home(path: &str) -> PathBuf {
+    if let Some(stripped) = path.strip_prefix("~/")
+        && let Some(home) = dirs::home_dir()
+    {
+        return home.join(stripped);
+    }
+    PathBuf::from(path)
+}
@@ -0,0 +1,633 @@
+use anyhow::{Context, Result, anyhow, bail};
+use hyper_util::client::legacy::connect::{Connected, Connection};
+use hyper_util::rt::TokioIo;
+use russh::client::{self, Handle, Msg};
+use russh::keys::agent::client::AgentClient;
+use russh::keys::{HashAlg, PrivateKey, PrivateKeyWithHashAlg, PublicKey, PublicKeyOrCertificate};
+use russh::{ChannelMsg, ChannelStream, Disconnect};
+use std::collections::HashMap;
+use std::future::Future;
+use std::io::{self, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::sync::{Arc, LazyLock, Mutex, RwLock};
+use std::task::Poll;
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+use tokio::time::timeout;
+
+use super::Forward;
+use super::target::{Hop, Target};
+use crate::config::HostKeyChecking;
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Connection chain shared with the connectors, swapped on reconnect.
+type SharedChain = Arc<RwLock<Arc<Chain>>>;
+
+/// SSH connection to the target host and the jump hosts it runs through.
+struct Chain {
+    handle: Handle<Client>,
+    /// Kept open for as long as `handle` is tunneled through them.
+    jumps: Vec<Handle<Client>>,
+}
+
+/// Authenticated in-process SSH session.
+pub struct Session {
+    chain: SharedChain,
+    forward: Option<Forward>,
+}
+
+impl Session {
+    /// Connect and authenticate; the API server is reached later through
+    /// channels opened by [`SshConnector`], to `forward` or else to whatever
+    /// address the client connects to.
+    pub async fn connect(
+        target: &Target,
+        forward: Option<&Forward>,
+        interactive: bool,
+    ) -> Result<Self> {
+        let chain = open(target, interactive).await?;
+        Ok(Session {
+            chain: Arc::new(RwLock::new(Arc::new(chain))),
+            forward: forward.cloned(),
+        })
+    }
+
+    /// Replace a lost connection. Connectors handed out earlier switch over to
+    /// the new one, so clients built on them keep working. Unknown host keys
+    /// are refused here, there is no terminal to ask on once the dashboard runs.
+    pub async fn reconnect(&self, target: &Target) -> Result<()> {
+        let chain = open(target, false).await?;
+        *self.chain.write().unwrap() = Arc::new(chain);
+        Ok(())
+    }
+
+    /// Whether the connection is gone, e.g. after unanswered keepalives on
+    /// the target or any jump host.
+    pub fn is_closed(&self) -> bool {
+        let chain = self.chain.read().unwrap();
+        chain.handle.is_closed() || chain.jumps.iter().any(|j| j.is_closed())
+    }
+
+    pub fn connector(&self) -> SshConnector {
+        SshConnector {
+            chain: self.chain.clone(),
+            forward: self.forward.clone(),
+        }
+    }
+
+    /// Shut down the session.
+    pub async fn close(self) -> Result<()> {
+        let chain = self.chain.read().unwrap().clone();
+        chain.disconnect().await;
+        Ok(())
+    }
+}
+
+impl Chain {
+    /// Close every connection, innermost first.
+    async fn disconnect(&self) {
+        for handle in std::iter::once(&self.handle).chain(self.jumps.iter().rev()) {
+            handle
+                .disconnect(Disconnect::ByApplication, "", "en")
+                .await
+                .ok();
+        }
+    }
+}
+
+/// Run `command` on the target host over a short-lived connection and return
+/// its output. With `interactive`, unknown host keys may be confirmed on the
+/// terminal.
+pub async fn exec(target: &Target, command: &str, interactive: bool) -> Result<String> {
+    let chain = open(target, interactive).await?;
+    let result = run(&chain.handle, command).await;
+    chain.disconnect().await;
+    result
+}
+
+async fn run(handle: &Handle<Client>, command: &str) -> Result<String> {
+    let mut channel = handle
+        .channel_open_session()
+        .await
+        .context("Failed to open SSH session channel")?;
+    channel
+        .exec(true, command)
+        .await
+        .context("Failed to run remote command")?;
+
+    let mut stdout = Vec::new();
+    let mut stderr = Vec::new();
+    let mut status = None;
+    while let Some(msg) = channel.wait().await {
+        match msg {
+            ChannelMsg::Data { data } => stdout.extend_from_slice(&data),
+            ChannelMsg::ExtendedData { data, .. } => stderr.extend_from_slice(&data),
+            ChannelMsg::ExitStatus { exit_status } => status = Some(exit_status),
+            _ => {}
+        }
+    }
+
+    match status {
+        Some(0) => String::from_utf8(stdout).context("Remote output is not valid UTF-8"),
+        _ => {
+            let stderr = String::from_utf8_lossy(&stderr);
+            match (stderr.trim(), status) {
+                ("", Some(code)) => bail!("remote command exited with status {}", code),
+                ("", None) => bail!("remote command ended without an exit status"),
+                (msg, _) => bail!("{}", msg),
+            }
+        }
+    }
+}
+
+/// Connect through the jump hosts, if any, and authenticate on each hop.
+/// `interactive` allows asking about unknown host keys on the terminal.
+async fn open(target: &Target, interactive: bool) -> Result<Chain> {
+    let config = Arc::new(client::Config {
+        // the session closes itself after keepalive_max unanswered keepalives
+        keepalive_interval: target.keepalive_interval,
+        keepalive_max: target.keepalive_count_max,
+        ..Default::default()
+    });
+
+    let mut handles: Vec<Handle<Client>> = Vec::new();
+    let hops = target.jumps.iter().chain(std::iter::once(&target.host));
+    for (i, hop) in hops.enumerate() {
+        let handler = Client {
+            host: hop.hostname.clone(),
+            port: hop.port,
+            known_hosts: target.known_hosts.clone(),
+            checking: target.ssh.host_key_checking,
+            // the pin is for the target host, jump hosts go by known_hosts
+            pinned: target
+                .ssh
+                .host_key_fingerprint
+                .clone()
+                .filter(|_| i == target.jumps.len()),
+            interactive,
+        };
+        let mut handle = connect_hop(config.clone(), hop, handles.last(), handler).await?;
+        authenticate(&mut handle, hop, interactive).await?;
+        handles.push(handle);
+    }
+
+    let handle = handles.pop().expect("the target host is always connected");
+    Ok(Chain {
+        handle,
+        jumps: handles,
+    })
+}
+
+/// Open the SSH connection to `hop`, directly or through a channel on the
+/// previous jump host.
+async fn connect_hop(
+    config: Arc<client::Config>,
+    hop: &Hop,
+    via: Option<&Handle<Client>>,
+    handler: Client,
+) -> Result<Handle<Client>> {
+    let connect = async {
+        match via {
+            None => client::connect(config, (hop.hostname.as_str(), hop.port), handler).await,
+            Some(jump) => {
+                let channel = jump
+                    .channel_open_direct_tcpip(&hop.hostname, hop.port as u32, "127.0.0.1", 0)
+                    .await
+                    .context("jump host refused to forward the connection")?;
+                client::connect_stream(config, channel.into_stream(), handler).await
+            }
+        }
+    };
+
+    timeout(CONNECT_TIMEOUT, connect)
+        .await
+        .map_err(|_| {
+            anyhow!(
+                "Timed out connecting to {}:{} after {}s",
+                hop.hostname,
+                hop.port,
+                CONNECT_TIMEOUT.as_secs()
+            )
+        })?
+        .with_context(|| format!("Failed to connect to {}:{}", hop.hostname, hop.port))
+}
+
+/// Try ssh-agent keys, then each identity file, until the server accepts one.
+/// With `interactive`, passphrases of encrypted keys are asked for.
+async fn authenticate(handle: &mut Handle<Client>, hop: &Hop, interactive: bool) -> Result<()> {
+    let rsa_hash: Option<HashAlg> = handle
+        .best_supported_rsa_hash()
+        .await
+        .context("SSH handshake failed")?
+        .flatten();
+
+    let mut tried = Vec::new();
+    let mut remaining = String::new();
+
+    if let Some(socket) = &hop.agent {
+        match AgentClient::connect_uds(socket).await {
+            Ok(mut agent) => {
+                let identities = agent
+                    .request_identities()
+                    .await
+                    .context("Failed to list ssh-agent keys")?;
+                // with IdentitiesOnly, only agent keys matching an identity file
+                let wanted: Vec<PublicKey> = hop
+                    .identity_files
+                    .iter()
+                    .filter_map(|p| public_key(p))
+                    .collect();
+
+                for identity in identities {
+                    let key = identity.public_key().into_owned();
+                    if hop.identities_only && !wanted.iter().any(|w| w.key_data() == key.key_data())
+                    {
+                        continue;
+                    }
+                    let result = handle
+                        .authenticate_publickey_with(&hop.user, key.clone(), rsa_hash, &mut agent)
+                        .await
+                        .context("SSH authentication failed")?;
+                    if accepted(result, &mut remaining) {
+                        return Ok(());
+                    }
+                    tried.push(match identity.comment() {
+                        "" => format!("agent key {}", key.fingerprint(HashAlg::Sha256)),
+                        comment => format!("agent key {}", comment),
+                    });
+                }
+            }
+            Err(e) => tried.push(format!("ssh-agent at {} ({})", socket.display(), e)),
+        }
+    }
+
+    for path in &hop.identity_files {
+        if !path.exists() {
+            tried.push(format!("{} (not found)", path.display()));
+            continue;
+        }
+        let Some(key) = load_key(path, interactive).await? else {
+            tried.push(format!(
+                "{} (passphrase-protected, add it to ssh-agent)",
+                path.display()
+            ));
+            continue;
+        };
+
+        let result = handle
+            .authenticate_publickey(&hop.user, PrivateKeyWithHashAlg::new(key, rsa_hash))
+            .await
+            .context("SSH authentication failed")?;
+        if accepted(result, &mut remaining) {
+            return Ok(());
+        }
+        tried.push(path.display().to_string());
+    }
+
+    if tried.is_empty() {
+        bail!(
+            "No SSH key found for {}: set ssh.key_path, add an IdentityFile to ~/.ssh/config or load a key into ssh-agent",
+            hop.hostname
+        );
+    }
+    bail!(
+        "Permission denied for {}@{} (tried {}); server accepts: {}",
+        hop.user,
+        hop.hostname,
+        tried.join(", "),
+        if remaining.is_empty() {
+            "-"
+        } else {
+            &remaining
+        }
+    )
+}
+
+/// Load an identity file. Encrypted keys are unlocked with a passphrase from
+/// the terminal and kept in memory for reconnects, when prism cannot ask;
+/// `None` when the key is encrypted and cannot be unlocked.
+async fn load_key(path: &Path, interactive: bool) -> Result<Option<Arc<PrivateKey>>> {
+    static UNLOCKED: LazyLock<Mutex<HashMap<PathBuf, Arc<PrivateKey>>>> =
+        LazyLock::new(Mutex::default);
+
+    match russh::keys::load_secret_key(path, None) {
+        Ok(key) => return Ok(Some(Arc::new(key))),
+        Err(russh::keys::Error::KeyIsEncrypted) => {}
+        Err(e) => {
+            return Err(e).with_context(|| format!("Failed to load SSH key {}", path.display()));
+        }
+    }
+    if let Some(key) = UNLOCKED.lock().unwrap().get(path) {
+        return Ok(Some(key.clone()));
+    }
+    if !interactive || !io::stdin().is_terminal() {
+        return Ok(None);
+    }
+
+    let prompt_path = path.to_path_buf();
+    let key = tokio::task::spawn_blocking(move || -> Result<Option<PrivateKey>> {
+        loop {
+            let passphrase = rpassword::prompt_password(format!(
+                "  Passphrase for {} (empty to skip): ",
+                prompt_path.display()
+            ))?;
+            if passphrase.is_empty() {
+                return Ok(None);
+            }
+            match russh::keys::load_secret_key(&prompt_path, Some(&passphrase)) {
+                Ok(key) => return Ok(Some(key)),
+                Err(_) => eprintln!("  Wrong passphrase, try again"),
+            }
+        }
+    })
+    .await??;
+    let Some(key) = key.map(Arc::new) else {
+        return Ok(None);
+    };
+    UNLOCKED
+        .lock()
+        .unwrap()
+        .insert(path.to_path_buf(), key.clone());
+    Ok(Some(key))
+}
+
+/// Whether authentication succeeded; on failure, records the methods the
+/// server would still accept.
+fn accepted(result: client::AuthResult, remaining: &mut String) -> bool {
+    match result {
+        client::AuthResult::Success => true,
+        client::AuthResult::Failure {
+            remaining_methods, ..
+        } => {
+            *remaining = remaining_methods
+                .iter()
+                .map(<&str>::from)
+                .collect::<Vec<_>>()
+                .join(",");
+            false
+        }
+    }
+}
+
+/// Public half of an identity file, from its `.pub` file or the key itself.
+fn public_key(path: &Path) -> Option<PublicKey> {
+    let mut pub_path = path.as_os_str().to_owned();
+    pub_path.push(".pub");
+    russh::keys::load_public_key(&pub_path).ok().or_else(|| {
+        russh::keys::load_secret_key(path, None)
+            .ok()
+            .map(|key| key.public_key().clone())
+    })
+}
+
+/// russh callbacks for the session.
+pub struct Client {
+    host: String,
+    port: u16,
+    known_hosts: PathBuf,
+    checking: HostKeyChecking,
+    /// Expected fingerprint, checked instead of known_hosts.
+    pinned: Option<String>,
+    interactive: bool,
+}
+
+impl client::Handler for Client {
+    type Error = anyhow::Error;
+
+    /// Verify the host key against the pinned fingerprint or known_hosts;
+    /// unknown keys are handled as `checking` says.
+    async fn check_server_key(&mut self, server_key: &PublicKeyOrCertificate) -> Result<bool> {
+        let PublicKeyOrCertificate::PublicKey { key, .. } = server_key else {
+            bail!(
+                "{} presented a host certificate, which is not supported",
+                self.host
+            );
+        };
+        let fingerprint = key.fingerprint(HashAlg::Sha256).to_string();
+
+        if let Some(pinned) = &self.pinned {
+            if fingerprint.trim_start_matches("SHA256:") == pinned.trim_start_matches("SHA256:") {
+                return Ok(true);
+            }
+            bail!(
+                "Host key for {} ({}) does not match ssh.host_key_fingerprint {}",
+                self.host,
+                fingerprint,
+                pinned
+            );
+        }
+
+        let known_hosts = self.known_hosts.display();
+        match russh::keys::check_known_hosts_path(&self.host, self.port, key, &self.known_hosts) {
+            Ok(true) => return Ok(true),
+            Ok(false) => {}
+            Err(russh::keys::Error::KeyChanged { line }) => bail!(
+                "Host key for {} has changed ({}); it does not match line {} of {}",
+                self.host,
+                fingerprint,
+                line,
+                known_hosts
+            ),
+            Err(e) => return Err(e).with_context(|| format!("Failed to read {}", known_hosts)),
+        }
+
+        let trusted = match self.checking {
+            HostKeyChecking::AcceptNew => true,
+            HostKeyChecking::Strict => bail!(
+                "Host key for {} ({}) is not in {}; add it (e.g. with ssh-keyscan) or set ssh.host_key_fingerprint",
+                self.host,
+                fingerprint,
+                known_hosts
+            ),
+            HostKeyChecking::Ask if !self.interactive => {
+                return Err(UnknownHostKey {
+                    host: self.host.clone(),
+                    port: self.port,
+                    key: key.clone(),
+                    known_hosts: self.known_hosts.clone(),
+                }
+                .into());
+            }
+            HostKeyChecking::Ask => self.confirm(key, &fingerprint).await?,
+        };
+        if !trusted {
+            bail!("Host key for {} was not accepted", self.host);
+        }
+
+        russh::keys::known_hosts::learn_known_hosts_path(
+            &self.host,
+            self.port,
+            key,
+            &self.known_hosts,
+        )
+        .with_context(|| format!("Failed to record host key in {}", known_hosts))?;
+        Ok(true)
+    }
+}
+
+impl Client {
+    /// Ask on the terminal whether to trust an unknown host key, as ssh does.
+    async fn confirm(&self, key: &PublicKey, fingerprint: &str) -> Result<bool> {
+        if !io::stdin().is_terminal() {
+            bail!(
+                "Host key for {} ({}) is not in {} and there is no terminal to confirm it; set ssh.host_key_fingerprint",
+                self.host,
+                fingerprint,
+                self.known_hosts.display()
+            );
+        }
+
+        let host = match self.port {
+            22 => self.host.clone(),
+            port => format!("{}:{}", self.host, port),
+        };
+        let prompt = format!(
+            "  The authenticity of host '{}' can't be established.\n  {} key fingerprint is {}.\n  Trust it and add it to {}? [y/N] ",
+            host,
+            key.algorithm(),
+            fingerprint,
+            self.known_hosts.display()
+        );
+        tokio::task::spawn_blocking(move || {
+            eprint!("{}", prompt);
+            io::stderr().flush().ok();
+            let mut answer = String::new();
+            io::stdin().read_line(&mut answer)?;
+            Ok(matches!(
+                answer.trim().to_ascii_lowercase().as_str(),
+                "y" | "yes"
+            ))
+        })
+        .await?
+    }
+}
+
+/// A host key that is not in known_hosts, met while no terminal could
+/// confirm it; the dashboard asks instead and records it with
+/// [`UnknownHostKey::trust`].
+#[derive(Debug)]
+pub struct UnknownHostKey {
+    pub host: String,
+    pub port: u16,
+    pub key: PublicKey,
+    pub known_hosts: PathBuf,
+}
+
+impl UnknownHostKey {
+    pub fn fingerprint(&self) -> String {
+        self.key.fingerprint(HashAlg::Sha256).to_string()
+    }
+
+    /// Add the key to known_hosts, so the next connection accepts it.
+    pub fn trust(&self) -> Result<()> {
+        russh::keys::known_hosts::learn_known_hosts_path(
+            &self.host,
+            self.port,
+            &self.key,
+            &self.known_hosts,
+        )
+        .with_context(|| {
+            format!(
+                "Failed to record host key in {}",
+                self.known_hosts.display()
+            )
+        })
+    }
+}
+
+impl std::fmt::Display for UnknownHostKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Host key for {} ({}) is not in {}",
+            self.host,
+            self.fingerprint(),
+            self.known_hosts.display()
+        )
+    }
+}
+
+impl std::error::Error for UnknownHostKey {}
+
+/// Connector for the kube client's HTTP stack: every connection becomes a
+/// `direct-tcpip` channel to the API server on the SSH host.
+#[derive(Clone)]
+pub struct SshConnector {
+    chain: SharedChain,
+    forward: Option<Forward>,
+}
+
+impl tower::Service<http::Uri> for SshConnector {
+    type Response = TokioIo<ChannelIo>;
+    type Error = io::Error;
+    type Future = Pin<Box<dyn Future<Output = io::Result<Self::Response>> + Send>>;
+
+    fn poll_ready(&mut self, _cx: &mut std::task::Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    // the uri only matters without a fixed forward, like a SOCKS proxy
+    fn call(&mut self, uri: http::Uri) -> Self::Future {
+        let chain = self.chain.read().unwrap().clone();
+        let forward = self.forward.clone().or_else(|| {
+            Some(Forward {
+                host: uri.host()?.trim_matches(['[', ']']).to_string(),
+                port: uri.port_u16().unwrap_or(443),
+            })
+        });
+        Box::pin(async move {
+            let forward = forward.ok_or_else(|| io::Error::other("API server URL has no host"))?;
+            let channel = chain
+                .handle
+                .channel_open_direct_tcpip(&forward.host, forward.port as u32, "127.0.0.1", 0)
+                .await
+                .map_err(|e| io::Error::other(format!("SSH channel to {}: {}", forward, e)))?;
+            Ok(TokioIo::new(ChannelIo(channel.into_stream())))
+        })
+    }
+}
+
+/// A `direct-tcpip` channel as a byte stream.
+pub struct ChannelIo(ChannelStream<Msg>);
+
+impl Connection for ChannelIo {
+    fn connected(&self) -> Connected {
+        Connected::new()
+    }
+}
+
+impl AsyncRead for ChannelIo {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.0).poll_read(cx, buf)
+    }
+}
+
+impl AsyncWrite for ChannelIo {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.0).poll_write(cx, buf)
+    }
+
+    fn poll_flush(
+        mut self: Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.0).poll_flush(cx)
+    }
+
+    fn poll_shutdown(
+        mut self: Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.0).poll_shutdown(cx)
+    }
+}
@@ -0,0 +1,286 @@
+use anyhow::{Context, Result};
+use std::process::Stdio;
+use std::time::Duration;
+use tokio::io::AsyncReadExt;
+use tokio::net::TcpStream;
+use tokio::process::{Child, Command};
+use tokio::time::{sleep, timeout};
+
+use super::{Forward, Target};
+use crate::config::{HostKeyChecking, SshConfig};
+
+const READY_TIMEOUT: Duration = Duration::from_secs(15);
+/// Used while ssh may be waiting for a host key confirmation.
+const PROMPT_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// `ssh -N -L` child process forwarding a local port, or `ssh -N -D`
+/// serving a SOCKS proxy on it.
+pub struct Tunnel {
+    child: Child,
+    local_port: u16,
+    socks: bool,
+}
+
+impl Tunnel {
+    /// Create an ssh tunnel that forwards `local_port` to `forward`, or a
+    /// SOCKS proxy on `local_port` without one. Without a `local_port`, a free
+    /// one is picked.
+    ///
+    /// The host is passed through as written, so ssh applies its own config
+    /// for it; prism's settings go on the command line and take precedence.
+    /// With `interactive`, ssh may ask about an unknown host key on the terminal.
+    pub async fn establish(
+        target: &Target,
+        local_port: Option<u16>,
+        forward: Option<&Forward>,
+        interactive: bool,
+    ) -> Result<Self> {
+        let ssh = &target.ssh;
+        let checking = host_key_checking(ssh, interactive)?;
+        let local_port = pick_port(local_port)?;
+
+        let mut cmd = ssh_command(target, checking, interactive);
+        cmd.arg("-N"); // no remote command
+        match forward {
+            Some(forward) => cmd
+                .arg("-L") // bind/forward ports?
+                .arg(format!("{}:{}", local_port, forward)),
+            None => cmd.arg("-D").arg(local_port.to_string()),
+        };
+        cmd.arg("-o").arg("ExitOnForwardFailure=yes").arg(&ssh.host);
+
+        // capture stderr to prevent leaks into tui
+        cmd.stderr(Stdio::piped());
+        cmd.stdout(Stdio::null());
+
+        // should not outlive app
+        cmd.kill_on_drop(true);
+
+        let child = cmd
+            .spawn()
+            .context("Failed to spawn ssh process. Is ssh installed?")?;
+
+        let mut tunnel = Tunnel {
+            child,
+            local_port,
+            socks: forward.is_none(),
+        };
+
+        // wait until the local port is reachable, leaving time to answer ssh's prompt
+        let ready_timeout = if checking == "ask" {
+            PROMPT_TIMEOUT
+        } else {
+            READY_TIMEOUT
+        };
+        if let Err(e) = tunnel.wait_for_port(local_port, ready_timeout).await {
+            let stderr_msg = tunnel.read_stderr().await;
+            let detail = if stderr_msg.is_empty() {
+                e.to_string()
+            } else {
+                stderr_msg
+            };
+            anyhow::bail!(
+                "SSH tunnel failed: {}",
+                explain(ssh, interactive, detail.trim())
+            );
+        }
+
+        Ok(tunnel)
+    }
+
+    pub fn local_port(&self) -> u16 {
+        self.local_port
+    }
+
+    pub fn is_socks(&self) -> bool {
+        self.socks
+    }
+
+    /// Whether the ssh process is still running.
+    pub fn is_alive(&mut self) -> bool {
+        matches!(self.child.try_wait(), Ok(None))
+    }
+
+    /// Why the ssh process exited, from its stderr.
+    pub async fn exit_reason(&mut self) -> String {
+        let stderr = self.read_stderr().await;
+        match stderr.trim() {
+            "" => "ssh process exited".to_string(),
+            msg => msg.to_string(),
+        }
+    }
+
+    /// Probe the local port until it accepts a TCP connection, or ssh gives up.
+    async fn wait_for_port(&mut self, port: u16, total_timeout: Duration) -> Result<()> {
+        let addr = format!("127.0.0.1:{}", port);
+
+        timeout(total_timeout, async {
+            loop {
+                if TcpStream::connect(&addr).await.is_ok() {
+                    return Ok(());
+                }
+                if !self.is_alive() {
+                    anyhow::bail!("ssh process exited");
+                }
+                sleep(Duration::from_millis(250)).await;
+            }
+        })
+        .await
+        .map_err(|_| {
+            anyhow::anyhow!(
+                "SSH tunnel did not become ready within {}s",
+                total_timeout.as_secs()
+            )
+        })?
+    }
+
+    /// Try to read any stderr output from the ssh process.
+    async fn read_stderr(&mut self) -> String {
+        if let Some(mut stderr) = self.child.stderr.take() {
+            let mut buf = String::new();
+            stderr.read_to_string(&mut buf).await.ok();
+            buf
+        } else {
+            String::new()
+        }
+    }
+
+    /// Shut down the tunnel.
+    pub async fn close(mut self) -> Result<()> {
+        self.child.kill().await.ok();
+        Ok(())
+    }
+}
+
+/// Run `command` on the SSH host and return its output. With `interactive`,
+/// ssh may ask about an unknown host key on the terminal.
+pub async fn exec(target: &Target, command: &str, interactive: bool) -> Result<String> {
+    let checking = host_key_checking(&target.ssh, interactive)?;
+    let output = ssh_command(target, checking, interactive)
+        .arg(&target.ssh.host)
+        .arg(command)
+        .stdin(Stdio::null())
+        .kill_on_drop(true)
+        .output()
+        .await
+        .context("Failed to spawn ssh process. Is ssh installed?")?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        match stderr.trim() {
+            "" => anyhow::bail!("ssh exited with {}", output.status),
+            msg => anyhow::bail!("{}", explain(&target.ssh, interactive, msg)),
+        }
+    }
+    String::from_utf8(output.stdout).context("Remote output is not valid UTF-8")
+}
+
+/// ssh with the connection options shared by tunnels and remote commands,
+/// up to but excluding the destination. Without `interactive`, ssh never
+/// prompts.
+fn ssh_command(target: &Target, checking: &str, interactive: bool) -> Command {
+    let ssh = &target.ssh;
+    let keepalive = target.keepalive_interval.map_or(0, |d| d.as_secs());
+
+    let mut cmd = Command::new("ssh");
+    cmd.arg("-o")
+        .arg(format!("StrictHostKeyChecking={}", checking))
+        .arg("-o")
+        .arg("ConnectTimeout=10")
+        .arg("-o") // exit when the connection silently dies
+        .arg(format!("ServerAliveInterval={}", keepalive))
+        .arg("-o")
+        .arg(format!(
+            "ServerAliveCountMax={}",
+            target.keepalive_count_max
+        ));
+    if !interactive {
+        cmd.arg("-o").arg("BatchMode=yes");
+    }
+
+    if let Some(ref file) = ssh.known_hosts {
+        cmd.arg("-o").arg(format!(
+            "UserKnownHostsFile={}",
+            super::expand_home(file).display()
+        ));
+    }
+    if let Some(ref file) = ssh.config_file {
+        cmd.arg("-F").arg(super::expand_home(file));
+    }
+    if let Some(port) = ssh.port {
+        cmd.arg("-p").arg(port.to_string()); // port to connect to on remote host
+    }
+    if let Some(ref user) = ssh.user {
+        cmd.arg("-l").arg(user);
+    }
+    for key in ssh.key_path.iter().chain(&ssh.identity_files) {
+        cmd.arg("-i").arg(super::expand_home(key));
+    }
+    if let Some(only) = ssh.identities_only {
+        cmd.arg("-o").arg(format!(
+            "IdentitiesOnly={}",
+            if only { "yes" } else { "no" }
+        ));
+    }
+    if !ssh.agent {
+        cmd.arg("-o").arg("IdentityAgent=none");
+    }
+    if let Some(ref jump) = ssh.proxy_jump {
+        cmd.arg("-J").arg(jump);
+    }
+    cmd
+}
+
+/// ssh's error, with a way out when it refused a host key it could not ask
+/// about.
+fn explain(ssh: &SshConfig, interactive: bool, msg: &str) -> String {
+    if !interactive
+        && ssh.host_key_checking == HostKeyChecking::Ask
+        && msg.contains("Host key verification failed")
+    {
+        format!(
+            "{}; the dashboard cannot ask about host keys with the openssh transport, connect with ssh once to confirm it or use the native transport",
+            msg
+        )
+    } else {
+        msg.to_string()
+    }
+}
+
+/// `StrictHostKeyChecking` value for the configured policy.
+fn host_key_checking(ssh: &SshConfig, interactive: bool) -> Result<&'static str> {
+    if ssh.host_key_fingerprint.is_some() {
+        anyhow::bail!("ssh.host_key_fingerprint is only supported by the native transport");
+    }
+    Ok(match ssh.host_key_checking {
+        HostKeyChecking::Ask if interactive => "ask",
+        HostKeyChecking::Ask | HostKeyChecking::Strict => "yes",
+        HostKeyChecking::AcceptNew => "accept-new",
+    })
+}
+
+/// Check that the configured port is free, or find a free one. The probe
+/// socket is closed again right before ssh binds the port.
+fn pick_port(configured: Option<u16>) -> Result<u16> {
+    let listener = match configured {
+        Some(port) => std::net::TcpListener::bind(("127.0.0.1", port)).with_context(|| {
+            format!(
+                "Local port {} is already in use; set kubernetes.local_port to a free port or remove it to pick one automatically",
+                port
+            )
+        })?,
+        None => std::net::TcpListener::bind(("127.0.0.1", 0))
+            .context("Failed to find a free local port")?,
+    };
+    Ok(listener.local_addr()?.port())
+}
+
+impl Drop for Tunnel {
+    fn drop(&mut self) {
+        // idk
+        #[allow(unused_must_use)]
+        {
+            self.child.start_kill().ok();
+        }
+    }
+}
@@ -0,0 +1,301 @@
+use anyhow::Result;
+use std::sync::{Arc, LazyLock, Mutex, PoisonError, Weak};
+use std::time::Duration;
+use tokio::sync::{Notify, watch};
+use tokio::task::JoinHandle;
+use tokio::time::sleep;
+
+use super::{Endpoint, Forward, SshTunnel, Target};
+
+/// How often the tunnel is checked.
+const HEALTH_INTERVAL: Duration = Duration::from_secs(2);
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+/// Reconnect attempts before giving up.
+const MAX_ATTEMPTS: u32 = 10;
+
+/// Tunnels handed out by [`Supervisor::share`], by the settings they were
+/// started with. A slot is locked while its tunnel comes up, so clusters
+/// connecting side by side wait for it instead of starting their own.
+type Slot = Arc<tokio::sync::Mutex<Weak<Supervisor>>>;
+static SHARED: LazyLock<Mutex<Vec<(Settings, Slot)>>> = LazyLock::new(Mutex::default);
+
+#[derive(Clone, PartialEq)]
+struct Settings {
+    target: Target,
+    local_port: Option<u16>,
+    forward: Option<Forward>,
+}
+
+/// Health of the SSH connection as shown in the dashboard header.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ConnectionState {
+    Connecting,
+    Connected,
+    /// Lost; waiting `retry_in` before attempt number `attempt`.
+    Reconnecting {
+        attempt: u32,
+        retry_in: Duration,
+        error: String,
+    },
+    /// Gave up after repeated failures.
+    Failed(String),
+}
+
+/// Keeps an [`SshTunnel`] up: watches it in the background and reconnects
+/// with exponential backoff when it drops. The endpoint never changes, so
+/// clients built on it resume once the tunnel is back.
+pub struct Supervisor {
+    endpoint: Endpoint,
+    state: watch::Receiver<ConnectionState>,
+    stop: Arc<Notify>,
+    task: JoinHandle<()>,
+}
+
+impl Supervisor {
+    /// Establish the tunnel, then start watching it. See
+    /// [`SshTunnel::establish`] for the arguments.
+    pub async fn start(
+        target: Target,
+        local_port: Option<u16>,
+        forward: Option<Forward>,
+        interactive: bool,
+    ) -> Result<Self> {
+        let (tx, state) = watch::channel(ConnectionState::Connecting);
+        let tunnel =
+            SshTunnel::establish(&target, local_port, forward.as_ref(), interactive).await?;
+        tx.send_replace(ConnectionState::Connected);
+
+        let endpoint = tunnel.endpoint();
+        let stop = Arc::new(Notify::new());
+        let task = tokio::spawn(supervise(tunnel, target, forward, tx, stop.clone()));
+
+        Ok(Supervisor {
+            endpoint,
+            state,
+            stop,
+            task,
+        })
+    }
+
+    /// Like [`Supervisor::start`], but hand out the tunnel already started
+    /// with the same settings while another cluster still uses it. One that
+    /// gave up reconnecting is started anew.
+    pub async fn share(
+        target: Target,
+        local_port: Option<u16>,
+        forward: Option<Forward>,
+        interactive: bool,
+    ) -> Result<Arc<Self>> {
+        let settings = Settings {
+            target,
+            local_port,
+            forward,
+        };
+        Self::shared(settings, |settings| {
+            Self::start(
+                settings.target,
+                settings.local_port,
+                settings.forward,
+                interactive,
+            )
+        })
+        .await
+    }
+
+    /// [`Supervisor::share`] with `start` to bring up a missing tunnel.
+    async fn shared<F>(settings: Settings, start: impl FnOnce(Settings) -> F) -> Result<Arc<Self>>
+    where
+        F: Future<Output = Result<Self>>,
+    {
+        let slot = {
+            let mut shared = SHARED.lock().unwrap_or_else(PoisonError::into_inner);
+            match shared.iter().find(|(s, _)| *s == settings) {
+                Some((_, slot)) => slot.clone(),
+                None => {
+                    let slot = Slot::default();
+                    shared.push((settings.clone(), slot.clone()));
+                    slot
+                }
+            }
+        };
+
+        let mut tunnel = slot.lock().await;
+        if let Some(tunnel) = tunnel.upgrade()
+            && !matches!(*tunnel.state.borrow(), ConnectionState::Failed(_))
+        {
+            return Ok(tunnel);
+        }
+        let started = Arc::new(start(settings).await?);
+        *tunnel = Arc::downgrade(&started);
+        Ok(started)
+    }
+
+    pub fn endpoint(&self) -> Endpoint {
+        self.endpoint.clone()
+    }
+
+    pub fn state(&self) -> watch::Receiver<ConnectionState> {
+        self.state.clone()
+    }
+
+    /// Stop watching and shut the tunnel down.
+    pub async fn close(self) -> Result<()> {
+        self.stop.notify_one();
+        self.task.await.ok();
+        Ok(())
+    }
+
+    /// Give up a tunnel from [`Supervisor::share`]; the last user closes it
+    /// and forgets its settings.
+    pub async fn release(self: Arc<Self>) -> Result<()> {
+        let Some(tunnel) = Arc::into_inner(self) else {
+            return Ok(());
+        };
+        // keep slots that are in use or have a tunnel coming up
+        SHARED
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .retain(|(_, slot)| {
+                Arc::strong_count(slot) > 1
+                    || slot
+                        .try_lock()
+                        .map_or(true, |tunnel| tunnel.strong_count() > 0)
+            });
+        tunnel.close().await
+    }
+}
+
+async fn supervise(
+    mut tunnel: SshTunnel,
+    target: Target,
+    forward: Option<Forward>,
+    state: watch::Sender<ConnectionState>,
+    stop: Arc<Notify>,
+) {
+    'watch: loop {
+        tokio::select! {
+            _ = sleep(HEALTH_INTERVAL) => {}
+            _ = stop.notified() => break,
+        }
+        let Some(mut error) = tunnel.lost().await else {
+            continue;
+        };
+
+        let mut backoff = INITIAL_BACKOFF;
+        for attempt in 1..=MAX_ATTEMPTS {
+            state.send_replace(ConnectionState::Reconnecting {
+                attempt,
+                retry_in: backoff,
+                error: error.clone(),
+            });
+            tokio::select! {
+                _ = sleep(backoff) => {}
+                _ = stop.notified() => break 'watch,
+            }
+
+            state.send_replace(ConnectionState::Connecting);
+            match tunnel.reconnect(&target, forward.as_ref()).await {
+                Ok(()) => {
+                    state.send_replace(ConnectionState::Connected);
+                    continue 'watch;
+                }
+                Err(e) => error = format!("{:#}", e),
+            }
+            backoff = (backoff * 2).min(MAX_BACKOFF);
+        }
+
+        state.send_replace(ConnectionState::Failed(error));
+        stop.notified().await;
+        break;
+    }
+
+    tunnel.close().await.ok();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::SshConfig;
+    use crate::ssh::target::Hop;
+
+    fn settings(host: &str) -> Settings {
+        let ssh: SshConfig = toml::from_str(&format!("host = \"{}\"", host)).unwrap();
+        Settings {
+            target: Target {
+                ssh,
+                host: Hop {
+                    hostname: host.into(),
+                    user: "prism".into(),
+                    port: 22,
+                    identity_files: Vec::new(),
+                    identities_only: false,
+                    agent: None,
+                },
+                jumps: Vec::new(),
+                keepalive_interval: None,
+                keepalive_count_max: 3,
+                known_hosts: "known_hosts".into(),
+            },
+            local_port: None,
+            forward: None,
+        }
+    }
+
+    /// A supervisor that watches nothing and reports `state`.
+    fn fake(state: ConnectionState) -> Supervisor {
+        Supervisor {
+            endpoint: Endpoint::Direct,
+            state: watch::channel(state).1,
+            stop: Arc::new(Notify::new()),
+            task: tokio::spawn(async {}),
+        }
+    }
+
+    fn is_shared(settings: &Settings) -> bool {
+        SHARED.lock().unwrap().iter().any(|(s, _)| s == settings)
+    }
+
+    #[tokio::test]
+    async fn share_hands_out_a_live_tunnel_and_forgets_it_when_released() {
+        let settings = settings("live.example.com");
+        let first = Supervisor::shared(settings.clone(), |_| async {
+            Ok(fake(ConnectionState::Connected))
+        })
+        .await
+        .unwrap();
+        let second = Supervisor::shared(settings.clone(), |_| async {
+            unreachable!("the running tunnel is shared")
+        })
+        .await
+        .unwrap();
+        assert!(Arc::ptr_eq(&first, &second));
+
+        first.release().await.unwrap();
+        assert!(is_shared(&settings));
+        second.release().await.unwrap();
+        assert!(!is_shared(&settings));
+    }
+
+    #[tokio::test]
+    async fn share_restarts_a_failed_tunnel() {
+        let settings = settings("failed.example.com");
+        let failed = Supervisor::shared(settings.clone(), |_| async {
+            Ok(fake(ConnectionState::Failed("gave up".into())))
+        })
+        .await
+        .unwrap();
+        let restarted = Supervisor::shared(settings.clone(), |_| async {
+            Ok(fake(ConnectionState::Connected))
+        })
+        .await
+        .unwrap();
+        assert!(!Arc::ptr_eq(&failed, &restarted));
+        assert_eq!(*restarted.state().borrow(), ConnectionState::Connected);
+
+        failed.release().await.unwrap();
+        assert!(is_shared(&settings));
+        restarted.release().await.unwrap();
+        assert!(!is_shared(&settings));
+    }
+}
@@ -0,0 +1,706 @@
+use anyhow::{Context, Result, bail};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::config::SshConfig;
+
+/// Keys tried, in order, when no identity is configured anywhere (as ssh does).
+const DEFAULT_KEYS: [&str; 3] = ["~/.ssh/id_ed25519", "~/.ssh/id_ecdsa", "~/.ssh/id_rsa"];
+const DEFAULT_KEEPALIVE_INTERVAL: u64 = 15;
+const DEFAULT_KEEPALIVE_COUNT_MAX: usize = 3;
+/// Longest ProxyJump chain followed, guards against alias loops.
+const MAX_JUMPS: usize = 8;
+const MAX_INCLUDE_DEPTH: usize = 16;
+
+/// The SSH host to connect to and the bastions in front of it, with
+/// prism's `[ssh]` settings layered over `~/.ssh/config`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Target {
+    /// Settings as written in prism's config, for transports that let ssh
+    /// read its own config.
+    pub ssh: SshConfig,
+    pub host: Hop,
+    /// Jump hosts in connection order, the first one is dialed directly.
+    pub jumps: Vec<Hop>,
+    /// `None` disables keepalives.
+    pub keepalive_interval: Option<Duration>,
+    pub keepalive_count_max: usize,
+    pub known_hosts: PathBuf,
+}
+
+/// One SSH server along the way.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Hop {
+    pub hostname: String,
+    pub user: String,
+    pub port: u16,
+    pub identity_files: Vec<PathBuf>,
+    pub identities_only: bool,
+    /// ssh-agent socket, `None` when agent authentication is off.
+    pub agent: Option<PathBuf>,
+}
+
+impl Target {
+    /// Resolve `ssh.host` through the ssh config file.
+    pub fn resolve(ssh: &SshConfig) -> Result<Self> {
+        let path = match &ssh.config_file {
+            Some(path) => super::expand_home(path),
+            None => super::expand_home("~/.ssh/config"),
+        };
+        let file = SshConfigFile::load(&path)?;
+
+        let settings = file.lookup(&ssh.host);
+        let mut identities: Vec<String> = ssh.key_path.iter().cloned().collect();
+        identities.extend(ssh.identity_files.iter().cloned());
+        let host = Hop::new(
+            &ssh.host,
+            &settings,
+            ssh.user.clone(),
+            ssh.port,
+            identities,
+            ssh.identities_only,
+            ssh.agent,
+        )?;
+
+        let jumps = match ssh.proxy_jump.as_ref().or(settings.proxy_jump.as_ref()) {
+            Some(spec) => file.jumps(spec, ssh.agent, 0)?,
+            None => Vec::new(),
+        };
+
+        let interval = ssh
+            .keepalive_interval
+            .or(settings.server_alive_interval)
+            .unwrap_or(DEFAULT_KEEPALIVE_INTERVAL);
+        Ok(Target {
+            ssh: ssh.clone(),
+            host,
+            jumps,
+            keepalive_interval: (interval > 0).then(|| Duration::from_secs(interval)),
+            keepalive_count_max: ssh
+                .keepalive_count_max
+                .or(settings.server_alive_count_max)
+                .unwrap_or(DEFAULT_KEEPALIVE_COUNT_MAX),
+            known_hosts: super::expand_home(
+                ssh.known_hosts
+                    .as_deref()
+                    .or(settings.user_known_hosts_file.as_deref())
+                    .unwrap_or("~/.ssh/known_hosts"),
+            ),
+        })
+    }
+
+    /// Keys set in prism's own `key_path` and `identity_files`, as resolved
+    /// for the host; the rest of its identity files come from the ssh config.
+    pub fn own_keys(&self) -> Vec<PathBuf> {
+        self.ssh
+            .key_path
+            .iter()
+            .chain(&self.ssh.identity_files)
+            .filter_map(|file| expand_tokens(file, &self.host.hostname, &self.host.user).ok())
+            .map(|file| super::expand_home(&file))
+            .collect()
+    }
+}
+
+impl fmt::Display for Target {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.host)?;
+        if !self.jumps.is_empty() {
+            let jumps: Vec<String> = self.jumps.iter().map(|j| j.to_string()).collect();
+            write!(f, " via {}", jumps.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+impl Hop {
+    /// Combine explicit settings with what the ssh config says for `alias`;
+    /// explicit ones win, like options on the ssh command line. Explicit
+    /// identities are tried before the config's, as with `ssh -i`.
+    fn new(
+        alias: &str,
+        settings: &HostSettings,
+        user: Option<String>,
+        port: Option<u16>,
+        identities: Vec<String>,
+        identities_only: Option<bool>,
+        agent: bool,
+    ) -> Result<Self> {
+        let user = match user.or_else(|| settings.user.clone()) {
+            Some(user) => user,
+            None => local_user()?,
+        };
+        let hostname = settings
+            .hostname
+            .as_deref()
+            .map(|h| h.replace("%h", alias))
+            .unwrap_or_else(|| alias.to_string());
+
+        let mut identity_files: Vec<PathBuf> = Vec::new();
+        for file in identities.iter().chain(&settings.identity_files) {
+            let path = super::expand_home(&expand_tokens(file, &hostname, &user)?);
+            if !identity_files.contains(&path) {
+                identity_files.push(path);
+            }
+        }
+        if identity_files.is_empty() {
+            identity_files = DEFAULT_KEYS
+                .iter()
+                .map(|k| super::expand_home(k))
+                .filter(|p| p.exists())
+                .collect();
+        }
+
+        let agent = match settings.identity_agent.as_deref() {
+            _ if !agent => None,
+            Some("none") => None,
+            Some("SSH_AUTH_SOCK") | None => std::env::var_os("SSH_AUTH_SOCK").map(PathBuf::from),
+            Some(path) => Some(super::expand_home(path)),
+        };
+
+        Ok(Hop {
+            hostname,
+            user,
+            port: port.or(settings.port).unwrap_or(22),
+            identity_files,
+            identities_only: identities_only
+                .or(settings.identities_only)
+                .unwrap_or(false),
+            agent,
+        })
+    }
+}
+
+impl fmt::Display for Hop {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}@{}:{}", self.user, self.hostname, self.port)
+    }
+}
+
+/// Host names in the ssh config at `path` that can be connected to as they
+/// are, leaving out patterns, in file order.
+pub fn host_aliases(path: &Path) -> Result<Vec<String>> {
+    let file = SshConfigFile::load(path)?;
+    let mut aliases: Vec<String> = Vec::new();
+    for line in file.lines.iter().filter(|line| line.keyword == "host") {
+        for alias in &line.args {
+            if !alias.contains(['*', '?', '!']) && !aliases.contains(alias) {
+                aliases.push(alias.clone());
+            }
+        }
+    }
+    Ok(aliases)
+}
+
+/// Options of one host from ssh_config(5). The first value found wins,
+/// except for `IdentityFile` which accumulates.
+#[derive(Debug, Default)]
+struct HostSettings {
+    hostname: Option<String>,
+    user: Option<String>,
+    port: Option<u16>,
+    identity_files: Vec<String>,
+    identities_only: Option<bool>,
+    identity_agent: Option<String>,
+    proxy_jump: Option<String>,
+    server_alive_interval: Option<u64>,
+    server_alive_count_max: Option<usize>,
+    user_known_hosts_file: Option<String>,
+}
+
+/// Parsed ssh config, `Include`s already inlined.
+struct SshConfigFile {
+    lines: Vec<Directive>,
+}
+
+struct Directive {
+    keyword: String,
+    args: Vec<String>,
+}
+
+impl SshConfigFile {
+    /// Read the config; a missing file is treated as empty.
+    fn load(path: &Path) -> Result<Self> {
+        let mut lines = Vec::new();
+        if path.exists() {
+            read_into(path, &mut lines, 0)?;
+        }
+        Ok(SshConfigFile { lines })
+    }
+
+    fn lookup(&self, host: &str) -> HostSettings {
+        let mut settings = HostSettings::default();
+        let mut active = true;
+
+        for line in &self.lines {
+            let value = line.args.first().map(String::as_str).unwrap_or_default();
+            match line.keyword.as_str() {
+                "host" => active = host_matches(&line.args, host),
+                // only `Match all` is understood, other criteria never match
+                "match" => active = line.args.iter().all(|a| a.eq_ignore_ascii_case("all")),
+                _ if !active => {}
+                "hostname" => set(&mut settings.hostname, value.to_string()),
+                "user" => set(&mut settings.user, value.to_string()),
+                "port" => {
+                    if let Ok(port) = value.parse() {
+                        set(&mut settings.port, port);
+                    }
+                }
+                "identityfile" => settings.identity_files.push(value.to_string()),
+                "identitiesonly" => set(&mut settings.identities_only, yes(value)),
+                "identityagent" => set(&mut settings.identity_agent, value.to_string()),
+                "proxyjump" => set(&mut settings.proxy_jump, value.to_string()),
+                "userknownhostsfile" => set(&mut settings.user_known_hosts_file, value.to_string()),
+                "serveraliveinterval" => {
+                    if let Ok(secs) = value.parse() {
+                        set(&mut settings.server_alive_interval, secs);
+                    }
+                }
+                "serveralivecountmax" => {
+                    if let Ok(count) = value.parse() {
+                        set(&mut settings.server_alive_count_max, count);
+                    }
+                }
+                _ => {}
+            }
+        }
+        settings
+    }
+
+    /// Expand a `ProxyJump` value, `[user@]host[:port]` hops separated by
+    /// commas. Jump hosts may have their own `ProxyJump`, which comes first.
+    fn jumps(&self, spec: &str, agent: bool, depth: usize) -> Result<Vec<Hop>> {
+        if spec.eq_ignore_ascii_case("none") {
+            return Ok(Vec::new());
+        }
+        if depth >= MAX_JUMPS {
+            bail!(
+                "ProxyJump chain is longer than {} hops; is there a loop?",
+                MAX_JUMPS
+            );
+        }
+
+        let mut hops = Vec::new();
+        for hop in spec.split(',').map(str::trim).filter(|h| !h.is_empty()) {
+            let (user, rest) = match hop.rsplit_once('@') {
+                Some((user, rest)) => (Some(user.to_string()), rest),
+                None => (None, hop),
+            };
+            let (host, port) = match rest.rsplit_once(':') {
+                Some((host, port)) => (
+                    host,
+                    Some(
+                        port.parse::<u16>()
+                            .with_context(|| format!("Invalid port in ProxyJump {}", hop))?,
+                    ),
+                ),
+                None => (rest, None),
+            };
+
+            let settings = self.lookup(host);
+            if let Some(inner) = &settings.proxy_jump {
+                hops.extend(self.jumps(inner, agent, depth + 1)?);
+            }
+            hops.push(Hop::new(
+                host,
+                &settings,
+                user,
+                port,
+                Vec::new(),
+                None,
+                agent,
+            )?);
+        }
+        Ok(hops)
+    }
+}
+
+/// Append the directives of `path`, following `Include`.
+fn read_into(path: &Path, lines: &mut Vec<Directive>, depth: usize) -> Result<()> {
+    if depth > MAX_INCLUDE_DEPTH {
+        bail!("Too many nested Includes at {}", path.display());
+    }
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read SSH config {}", path.display()))?;
+
+    for raw in content.lines() {
+        let line = raw.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (keyword, rest) = line
+            .split_once(|c: char| c.is_whitespace() || c == '=')
+            .unwrap_or((line, ""));
+        let rest = rest.trim_start().trim_start_matches('=');
+        let directive = Directive {
+            keyword: keyword.to_ascii_lowercase(),
+            args: split_args(rest),
+        };
+
+        if directive.keyword == "include" {
+            for pattern in &directive.args {
+                for file in include_files(pattern) {
+                    read_into(&file, lines, depth + 1)?;
+                }
+            }
+        } else {
+            lines.push(directive);
+        }
+    }
+    Ok(())
+}
+
+/// Files named by an `Include` argument. Relative paths are taken from
+/// `~/.ssh`, and `*`/`?` are matched within the last path component.
+fn include_files(pattern: &str) -> Vec<PathBuf> {
+    let path = super::expand_home(pattern);
+    let path = if path.is_absolute() {
+        path
+    } else {
+        super::expand_home("~/.ssh").join(path)
+    };
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    if !name.contains(['*', '?']) {
+        return vec![path].into_iter().filter(|p| p.is_file()).collect();
+    }
+
+    let Some(dir) = path.parent() else {
+        return Vec::new();
+    };
+    let mut files: Vec<PathBuf> = std::fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|p| {
+            p.is_file()
+                && p.file_name()
+                    .is_some_and(|n| wildcard(&name, &n.to_string_lossy()))
+        })
+        .collect();
+    files.sort();
+    files
+}
+
+/// Split arguments on whitespace, honouring double quotes.
+fn split_args(rest: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+    for c in rest.chars() {
+        match c {
+            '"' => quoted = !quoted,
+            c if c.is_whitespace() && !quoted => {
+                if !current.is_empty() {
+                    args.push(std::mem::take(&mut current));
+                }
+            }
+            c => current.push(c),
+        }
+    }
+    if !current.is_empty() {
+        args.push(current);
+    }
+    args
+}
+
+/// `Host` patterns: any positive match, and no negated (`!pattern`) one.
+fn host_matches(patterns: &[String], host: &str) -> bool {
+    let mut matched = false;
+    for pattern in patterns {
+        if let Some(negated) = pattern.strip_prefix('!') {
+            if wildcard(negated, host) {
+                return false;
+            }
+        } else if wildcard(pattern, host) {
+            matched = true;
+        }
+    }
+    matched
+}
+
+/// Glob match supporting `*` and `?`, case-insensitive like ssh.
+fn wildcard(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.to_ascii_lowercase().chars().collect();
+    let text: Vec<char> = text.to_ascii_lowercase().chars().collect();
+    let (mut p, mut t) = (0, 0);
+    let mut star: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            star = Some((p, t));
+            p += 1;
+        } else if let Some((sp, st)) = star {
+            p = sp + 1;
+            t = st + 1;
+            star = Some((sp, st + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// Expand the ssh_config tokens used in `IdentityFile` paths.
+fn expand_tokens(value: &str, hostname: &str, user: &str) -> Result<String> {
+    let home = dirs::home_dir()
+        .map(|h| h.display().to_string())
+        .unwrap_or_default();
+    let mut out = String::new();
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('h') => out.push_str(hostname),
+            Some('r') => out.push_str(user),
+            Some('u') => out.push_str(&local_user()?),
+            Some('d') => out.push_str(&home),
+            Some('%') => out.push('%'),
+            Some(other) => {
+                out.push('%');
+                out.push(other);
+            }
+            None => out.push('%'),
+        }
+    }
+    Ok(out)
+}
+
+fn set<T>(slot: &mut Option<T>, value: T) {
+    if slot.is_none() {
+        *slot = Some(value);
+    }
+}
+
+fn yes(value: &str) -> bool {
+    value.eq_ignore_ascii_case("yes") || value.eq_ignore_ascii_case("true")
+}
+
+/// The login name ssh would use by default.
+fn local_user() -> Result<String> {
+    std::env::var("USER")
+        .or_else(|_| std::env::var("USERNAME"))
+        .ok()
+        .filter(|user| !user.is_empty())
+        .context("No SSH user configured and $USER is not set; set ssh.user")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Write `files` into a fresh directory under the temp dir.
+    fn temp_dir(name: &str, files: &[(&str, &str)]) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("prism-target-{}-{}", std::process::id(), name));
+        std::fs::remove_dir_all(&dir).ok();
+        std::fs::create_dir_all(&dir).unwrap();
+        for (file, content) in files {
+            std::fs::write(dir.join(file), content).unwrap();
+        }
+        dir
+    }
+
+    fn strings(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn wildcard_matches_star_and_question_mark() {
+        assert!(wildcard("*", ""));
+        assert!(wildcard("*.example.com", "db.example.com"));
+        assert!(wildcard("web-?", "web-1"));
+        assert!(wildcard("*db*", "prod-db-1"));
+        assert!(wildcard("Prod", "prod"));
+        assert!(!wildcard("web-?", "web-10"));
+        assert!(!wildcard("*.example.com", "example.com"));
+        assert!(!wildcard("db", "db2"));
+    }
+
+    #[test]
+    fn host_matches_needs_a_positive_match_and_no_negated_one() {
+        let patterns = strings(&["*.example.com", "!bastion.example.com"]);
+        assert!(host_matches(&patterns, "db.example.com"));
+        assert!(!host_matches(&patterns, "bastion.example.com"));
+        assert!(!host_matches(&patterns, "db.example.org"));
+        // a negation alone matches nothing
+        assert!(!host_matches(&strings(&["!db"]), "web"));
+    }
+
+    #[test]
+    fn split_args_honours_quotes() {
+        assert_eq!(split_args("a  b\tc"), strings(&["a", "b", "c"]));
+        assert_eq!(
+            split_args(r#""/path/with space/key" other"#),
+            strings(&["/path/with space/key", "other"])
+        );
+        assert_eq!(split_args(r#"""#), Vec::<String>::new());
+        assert!(split_args("   ").is_empty());
+    }
+
+    #[test]
+    fn expand_tokens_replaces_host_and_remote_user() {
+        assert_eq!(
+            expand_tokens("~/.ssh/%r@%h_%%", "db.example.com", "admin").unwrap(),
+            "~/.ssh/admin@db.example.com_%"
+        );
+        assert_eq!(expand_tokens("key%x%", "h", "u").unwrap(), "key%x%");
+    }
+
+    #[test]
+    fn lookup_takes_the_first_value_and_accumulates_identities() {
+        let dir = temp_dir(
+            "lookup",
+            &[(
+                "config",
+                "Host db !skip\n  HostName 10.0.0.5\n  User admin\n  IdentityFile ~/.ssh/db\n\
+                 Host *\n  User nobody\n  Port=2222\n  IdentityFile ~/.ssh/all\n",
+            )],
+        );
+        let file = SshConfigFile::load(&dir.join("config")).unwrap();
+
+        let db = file.lookup("db");
+        assert_eq!(db.hostname.as_deref(), Some("10.0.0.5"));
+        assert_eq!(db.user.as_deref(), Some("admin"));
+        assert_eq!(db.port, Some(2222));
+        assert_eq!(db.identity_files, strings(&["~/.ssh/db", "~/.ssh/all"]));
+
+        let skip = file.lookup("skip");
+        assert_eq!(skip.hostname, None);
+        assert_eq!(skip.user.as_deref(), Some("nobody"));
+    }
+
+    #[test]
+    fn include_inlines_matching_files_in_order() {
+        let dir = temp_dir(
+            "include",
+            &[
+                ("b.conf", "Host b\n  User from-b\n"),
+                ("a.conf", "Host a\n  User from-a\n"),
+                ("skipped.txt", "Host a\n  User from-txt\n"),
+            ],
+        );
+        let config = format!(
+            "Include {}/*.conf\nHost *\n  User fallback\n",
+            dir.display()
+        );
+        std::fs::write(dir.join("config"), config).unwrap();
+        let file = SshConfigFile::load(&dir.join("config")).unwrap();
+
+        assert_eq!(file.lookup("a").user.as_deref(), Some("from-a"));
+        assert_eq!(file.lookup("b").user.as_deref(), Some("from-b"));
+        assert_eq!(file.lookup("c").user.as_deref(), Some("fallback"));
+    }
+
+    #[test]
+    fn include_loops_are_cut_off() {
+        let dir = temp_dir("include-loop", &[]);
+        let config = dir.join("config");
+        std::fs::write(&config, format!("Include {}\n", config.display())).unwrap();
+        assert!(SshConfigFile::load(&config).is_err());
+    }
+
+    #[test]
+    fn jumps_split_hops_and_follow_nested_proxy_jump() {
+        let dir = temp_dir(
+            "jumps",
+            &[(
+                "config",
+                "Host inner\n  HostName 10.0.0.2\n  User ops\n  ProxyJump outer\n\
+                 Host outer\n  HostName outer.example.com\n  User gate\n  Port 2200\n",
+            )],
+        );
+        let file = SshConfigFile::load(&dir.join("config")).unwrap();
+
+        let hops = file.jumps("admin@first:2222, inner", false, 0).unwrap();
+        let hops: Vec<String> = hops.iter().map(Hop::to_string).collect();
+        assert_eq!(
+            hops,
+            strings(&[
+                "admin@first:2222",
+                "gate@outer.example.com:2200",
+                "ops@10.0.0.2:22",
+            ])
+        );
+        assert!(file.jumps("none", false, 0).unwrap().is_empty());
+        assert!(file.jumps("admin@first:port", false, 0).is_err());
+    }
+
+    #[test]
+    fn jumps_refuse_loops() {
+        let dir = temp_dir(
+            "jump-loop",
+            &[(
+                "config",
+                "Host a\n  User u\n  ProxyJump b\nHost b\n  User u\n  ProxyJump a\n",
+            )],
+        );
+        let file = SshConfigFile::load(&dir.join("config")).unwrap();
+        assert!(file.jumps("a", false, 0).is_err());
+    }
+
+    #[test]
+    fn explicit_identities_come_before_the_configs() {
+        let settings = HostSettings {
+            identity_files: strings(&["/keys/config", "/keys/both"]),
+            ..Default::default()
+        };
+        let hop = Hop::new(
+            "db",
+            &settings,
+            Some("admin".into()),
+            None,
+            strings(&["/keys/explicit", "/keys/both"]),
+            None,
+            false,
+        )
+        .unwrap();
+        assert_eq!(
+            hop.identity_files,
+            ["/keys/explicit", "/keys/both", "/keys/config"]
+                .map(PathBuf::from)
+                .to_vec()
+        );
+    }
+
+    #[test]
+    fn own_keys_are_the_ones_from_prisms_config() {
+        let dir = temp_dir(
+            "own-keys",
+            &[(
+                "config",
+                "Host db
+  User admin
+  IdentityFile /keys/config
+",
+            )],
+        );
+        let ssh: SshConfig = toml::from_str(&format!(
+            "host = \"db\"\nkey_path = \"/keys/%h\"\nidentity_files = [\"/keys/extra\"]\n\
+             config_file = \"{}\"\n",
+            dir.join("config").display()
+        ))
+        .unwrap();
+        let target = Target::resolve(&ssh).unwrap();
+        assert_eq!(
+            target.host.identity_files,
+            ["/keys/db", "/keys/extra", "/keys/config"]
+                .map(PathBuf::from)
+                .to_vec()
+        );
+        assert_eq!(
+            target.own_keys(),
+            ["/keys/db", "/keys/extra"].map(PathBuf::from).to_vec()
+        );
+    }
+}
@@ -3,7 +3,10 @@ use ratatui::layout::{Constraint, Layout, Rect};
 use ratatui::text::{Line, Span};
 use ratatui::widgets::{Block, Borders, Cell, Paragraph, Row, Table};
 
-use crate::app::{App, Panel};
+use crate::app::{App, Panel, Scope};
+use crate::k8s::metrics;
+use crate::k8s::{PodInfo, ResourceKind};
+use crate::ssh::ConnectionState;
 use crate::ui::theme;
 
 pub fn render(f: &mut Frame, app: &mut App) {
@@ -11,6 +14,7 @@ pub fn render(f: &mut Frame, app: &mut App) {
         Constraint::Length(3), // header
         Constraint::Min(5),    // pods
         Constraint::Min(5),    // deployments
+        Constraint::Length(nodes_height(app)),
         Constraint::Length(1), // footer
     ])
     .split(f.area());
@@ -18,17 +22,42 @@ pub fn render(f: &mut Frame, app: &mut App) {
     render_header(f, chunks[0], app);
     render_pods(f, chunks[1], app);
     render_deployments(f, chunks[2], app);
-    render_footer(f, chunks[3]);
+    render_nodes(f, chunks[3], app);
+    render_footer(f, chunks[4], app);
+}
+
+/// Nodes are few, size the panel to fit them (borders and header included).
+fn nodes_height(app: &App) -> u16 {
+    app.nodes.len().clamp(1, 5) as u16 + 4
 }
 
 fn render_header(f: &mut Frame, area: Rect, app: &App) {
-    let status = if app.error.is_some() {
-        Span::styled(" ERROR ", theme::status_style("Failed"))
-    } else {
-        Span::styled(" Connected ", theme::status_style("Running"))
+    let all = app.scope == Some(Scope::All) && app.switching.is_none();
+    let status = match &app.connection {
+        // every cluster has its own tunnel, count the ones that listed
+        _ if all => {
+            let total = app.clusters.len();
+            let up = total.saturating_sub(app.cluster_errors.len());
+            let status = match up {
+                0 => "Failed",
+                _ if up < total => "Pending",
+                _ => "Running",
+            };
+            Span::styled(
+                format!(" {}/{} connected ", up, total),
+                theme::status_style(status),
+            )
+        }
+        ConnectionState::Connecting => Span::styled(" Connecting ", theme::status_style("Pending")),
+        ConnectionState::Connected => Span::styled(" Connected ", theme::status_style("Running")),
+        ConnectionState::Reconnecting { attempt, .. } => Span::styled(
+            format!(" Reconnecting (attempt {}) ", attempt),
+            theme::status_style("Pending"),
+        ),
+        ConnectionState::Failed(_) => Span::styled(" Disconnected ", theme::status_style("Failed")),
     };
 
-    let title = Line::from(vec![
+    let mut title = Line::from(vec![
         Span::styled("  Prism", theme::header()),
         Span::styled(" │ ", ratatui::style::Style::default().fg(theme::BORDER)),
         Span::styled(
@@ -36,14 +65,86 @@ fn render_header(f: &mut Frame, area: Rect, app: &App) {
             ratatui::style::Style::default().fg(theme::TEXT_DIM),
         ),
         Span::raw("  "),
-        status,
     ]);
+    if let Some(scope) = &app.scope {
+        title.push_span(Span::styled(format!("{} ", scope), theme::header()));
+    }
+    if let Some(context) = &app.context {
+        title.push_span(Span::styled(
+            format!("{} ", context),
+            ratatui::style::Style::default().fg(theme::TEXT_DIM),
+        ));
+    }
+    title.push_span(status);
+    if app.metrics_available == Some(false) {
+        title.push_span(Span::styled(
+            "  metrics-server not available, CPU/MEM hidden",
+            ratatui::style::Style::default().fg(theme::YELLOW),
+        ));
+    }
+
+    // tunnel trouble first, then the latest error or the outcome of the last action
+    let detail = if let ConnectionState::Reconnecting {
+        retry_in, error, ..
+    } = &app.connection
+    {
+        Line::from(Span::styled(
+            format!(
+                "  Connection lost: {} (retrying in {}s)",
+                error,
+                retry_in.as_secs()
+            ),
+            ratatui::style::Style::default().fg(theme::YELLOW),
+        ))
+    } else if let ConnectionState::Failed(error) = &app.connection {
+        let hint = match (app.clusters.is_empty(), app.contexts.is_empty()) {
+            (true, true) => "restart prism",
+            (true, false) => "restart prism or press x to pick a context",
+            (false, _) => "restart prism or press c to pick a cluster",
+        };
+        Line::from(Span::styled(
+            format!("  Not connected: {} ({})", error, hint),
+            ratatui::style::Style::default().fg(theme::RED),
+        ))
+    } else if !app.cluster_errors.is_empty() {
+        let errors: Vec<String> = app
+            .cluster_errors
+            .iter()
+            .map(|(cluster, error)| format!("{}: {}", cluster, error))
+            .collect();
+        Line::from(Span::styled(
+            format!("  {}", errors.join("  ·  ")),
+            ratatui::style::Style::default().fg(theme::RED),
+        ))
+    } else if !app.cluster_warnings.is_empty() {
+        let warnings: Vec<String> = app
+            .cluster_warnings
+            .iter()
+            .map(|(cluster, warning)| format!("{}: {}", cluster, warning))
+            .collect();
+        Line::from(Span::styled(
+            format!("  {}", warnings.join("  ·  ")),
+            ratatui::style::Style::default().fg(theme::YELLOW),
+        ))
+    } else if let Some(err) = &app.error {
+        Line::from(Span::styled(
+            format!("  {}", err),
+            ratatui::style::Style::default().fg(theme::RED),
+        ))
+    } else if let Some(msg) = &app.message {
+        Line::from(Span::styled(
+            format!("  {}", msg),
+            ratatui::style::Style::default().fg(theme::TEXT_DIM),
+        ))
+    } else {
+        Line::default()
+    };
 
     let block = Block::default()
         .borders(Borders::BOTTOM)
         .border_style(ratatui::style::Style::default().fg(theme::BORDER));
 
-    let header = Paragraph::new(title).block(block);
+    let header = Paragraph::new(vec![title, detail]).block(block);
     f.render_widget(header, area);
 }
 
@@ -51,39 +152,90 @@ fn render_pods(f: &mut Frame, area: Rect, app: &mut App) {
     let focused = app.active_panel == Panel::Pods;
 
     let rows: Vec<Row> = app
-        .pods
-        .iter()
+        .visible_pods()
+        .into_iter()
         .map(|p| {
-            Row::new(vec![
-                Cell::from(p.name.clone()).style(theme::row_normal()),
-                Cell::from(p.namespace.clone())
-                    .style(ratatui::style::Style::default().fg(theme::TEXT_DIM)),
-                Cell::from(p.status.clone()).style(theme::status_style(&p.status)),
-                Cell::from(p.restarts.to_string()).style(theme::row_normal()),
-                Cell::from(p.age.clone())
-                    .style(ratatui::style::Style::default().fg(theme::TEXT_DIM)),
-            ])
+            // deleted but not yet gone from the listing
+            let terminating = app
+                .terminating
+                .iter()
+                .any(|t| t.kind == ResourceKind::Pod && t.uid == p.uid);
+            let status = if terminating {
+                "Terminating"
+            } else {
+                p.status.as_str()
+            };
+
+            let marked = app
+                .selection
+                .iter()
+                .any(|r| r.kind == ResourceKind::Pod && r.uid == p.uid);
+
+            with_cluster(
+                app,
+                &p.cluster,
+                vec![
+                    name_cell(&p.name, marked),
+                    Cell::from(p.namespace.clone())
+                        .style(ratatui::style::Style::default().fg(theme::TEXT_DIM)),
+                    Cell::from(status.to_string()).style(theme::status_style(status)),
+                    restarts_cell(p, app),
+                    usage_cell(
+                        p.usage.map(|u| u.cpu_millis),
+                        p.cpu_request,
+                        p.cpu_limit,
+                        metrics::format_cpu,
+                    ),
+                    usage_cell(
+                        p.usage.map(|u| u.memory_bytes),
+                        p.mem_request,
+                        p.mem_limit,
+                        metrics::format_memory,
+                    ),
+                    Cell::from(p.age.clone())
+                        .style(ratatui::style::Style::default().fg(theme::TEXT_DIM)),
+                ],
+            )
         })
         .collect();
 
-    let header = Row::new(vec!["NAME", "NAMESPACE", "STATUS", "RESTARTS", "AGE"])
-        .style(theme::table_header())
-        .bottom_margin(1);
+    let header = Row::new(with_cluster_header(
+        app,
+        vec![
+            "NAME",
+            "NAMESPACE",
+            "STATUS",
+            "RESTARTS",
+            "CPU %REQ/%LIM",
+            "MEM %REQ/%LIM",
+            "AGE",
+        ],
+    ))
+    .style(theme::table_header())
+    .bottom_margin(1);
 
     let block = Block::default()
-        .title(Span::styled(" Pods ", theme::header()))
+        .title(Span::styled(
+            panel_title("Pods", app, true),
+            theme::header(),
+        ))
         .borders(Borders::ALL)
         .border_style(theme::border(focused));
 
     let table = Table::new(
         rows,
-        [
-            Constraint::Percentage(30),
-            Constraint::Percentage(20),
-            Constraint::Percentage(20),
-            Constraint::Percentage(15),
-            Constraint::Percentage(15),
-        ],
+        with_cluster_width(
+            app,
+            vec![
+                Constraint::Percentage(26),
+                Constraint::Percentage(13),
+                Constraint::Percentage(14),
+                Constraint::Percentage(9),
+                Constraint::Percentage(15),
+                Constraint::Percentage(15),
+                Constraint::Percentage(8),
+            ],
+        ),
     )
     .header(header)
     .block(block)
@@ -96,39 +248,77 @@ fn render_deployments(f: &mut Frame, area: Rect, app: &mut App) {
     let focused = app.active_panel == Panel::Deployments;
 
     let rows: Vec<Row> = app
-        .deployments
-        .iter()
+        .visible_deployments()
+        .into_iter()
         .map(|d| {
-            Row::new(vec![
-                Cell::from(d.name.clone()).style(theme::row_normal()),
-                Cell::from(d.namespace.clone())
-                    .style(ratatui::style::Style::default().fg(theme::TEXT_DIM)),
-                Cell::from(d.ready.clone()).style(theme::row_normal()),
-                Cell::from(d.up_to_date.to_string()).style(theme::row_normal()),
-                Cell::from(d.age.clone())
-                    .style(ratatui::style::Style::default().fg(theme::TEXT_DIM)),
-            ])
+            let scaling = app
+                .scaling
+                .iter()
+                .find(|t| t.kind == d.kind && t.namespace == d.namespace && t.name == d.name);
+            let terminating = app
+                .terminating
+                .iter()
+                .any(|t| t.kind == ResourceKind::from(d.kind) && t.uid == d.uid);
+            let ready = match scaling {
+                _ if terminating => {
+                    Cell::from("Terminating").style(theme::status_style("Terminating"))
+                }
+                Some(t) => Cell::from(format!("{}/{} → {}", d.ready, d.desired, t.target))
+                    .style(theme::status_style("Pending")),
+                None => Cell::from(format!("{}/{}", d.ready, d.desired)).style(theme::row_normal()),
+            };
+
+            let marked = app
+                .selection
+                .iter()
+                .any(|r| r.kind == ResourceKind::from(d.kind) && r.uid == d.uid);
+
+            with_cluster(
+                app,
+                &d.cluster,
+                vec![
+                    name_cell(&d.name, marked),
+                    Cell::from(d.namespace.clone())
+                        .style(ratatui::style::Style::default().fg(theme::TEXT_DIM)),
+                    Cell::from(d.kind.as_str())
+                        .style(ratatui::style::Style::default().fg(theme::TEXT_DIM)),
+                    ready,
+                    Cell::from(d.up_to_date.to_string()).style(theme::row_normal()),
+                    Cell::from(d.age.clone())
+                        .style(ratatui::style::Style::default().fg(theme::TEXT_DIM)),
+                ],
+            )
         })
         .collect();
 
-    let header = Row::new(vec!["NAME", "NAMESPACE", "READY", "UP-TO-DATE", "AGE"])
-        .style(theme::table_header())
-        .bottom_margin(1);
+    let header = Row::new(with_cluster_header(
+        app,
+        vec!["NAME", "NAMESPACE", "KIND", "READY", "UP-TO-DATE", "AGE"],
+    ))
+    .style(theme::table_header())
+    .bottom_margin(1);
 
     let block = Block::default()
-        .title(Span::styled(" Deployments ", theme::header()))
+        .title(Span::styled(
+            panel_title("Deployments", app, false),
+            theme::header(),
+        ))
         .borders(Borders::ALL)
         .border_style(theme::border(focused));
 
     let table = Table::new(
         rows,
-        [
-            Constraint::Percentage(30),
-            Constraint::Percentage(20),
-            Constraint::Percentage(20),
-            Constraint::Percentage(15),
-            Constraint::Percentage(15),
-        ],
+        with_cluster_width(
+            app,
+            vec![
+                Constraint::Percentage(28),
+                Constraint::Percentage(17),
+                Constraint::Percentage(14),
+                Constraint::Percentage(15),
+                Constraint::Percentage(13),
+                Constraint::Percentage(13),
+            ],
+        ),
     )
     .header(header)
     .block(block)
@@ -137,8 +327,230 @@ fn render_deployments(f: &mut Frame, area: Rect, app: &mut App) {
     f.render_stateful_widget(table, area, &mut app.deployments_state);
 }
 
-fn render_footer(f: &mut Frame, area: Rect) {
-    let keys = Line::from(vec![
+fn render_nodes(f: &mut Frame, area: Rect, app: &mut App) {
+    let focused = app.active_panel == Panel::Nodes;
+
+    let rows: Vec<Row> = app
+        .visible_nodes()
+        .into_iter()
+        .map(|n| {
+            let status = if n.status.starts_with("Ready") {
+                "Running"
+            } else {
+                "Failed"
+            };
+            with_cluster(
+                app,
+                &n.cluster,
+                vec![
+                    Cell::from(n.name.clone()).style(theme::row_normal()),
+                    Cell::from(n.status.clone()).style(theme::status_style(status)),
+                    Cell::from(n.roles.clone())
+                        .style(ratatui::style::Style::default().fg(theme::TEXT_DIM)),
+                    Cell::from(n.version.clone())
+                        .style(ratatui::style::Style::default().fg(theme::TEXT_DIM)),
+                    allocatable_cell(
+                        n.usage.map(|u| u.cpu_millis),
+                        n.cpu_allocatable,
+                        metrics::format_cpu,
+                    ),
+                    allocatable_cell(
+                        n.usage.map(|u| u.memory_bytes),
+                        n.mem_allocatable,
+                        metrics::format_memory,
+                    ),
+                    Cell::from(n.age.clone())
+                        .style(ratatui::style::Style::default().fg(theme::TEXT_DIM)),
+                ],
+            )
+        })
+        .collect();
+
+    let header = Row::new(with_cluster_header(
+        app,
+        vec!["NAME", "STATUS", "ROLES", "VERSION", "CPU", "MEM", "AGE"],
+    ))
+    .style(theme::table_header())
+    .bottom_margin(1);
+
+    let block = Block::default()
+        .title(Line::from(vec![
+            Span::styled(" Nodes ", theme::header()),
+            Span::styled(
+                if app.nodes_available {
+                    ""
+                } else {
+                    "(not allowed to list) "
+                },
+                ratatui::style::Style::default().fg(theme::TEXT_DIM),
+            ),
+        ]))
+        .borders(Borders::ALL)
+        .border_style(theme::border(focused));
+
+    let table = Table::new(
+        rows,
+        with_cluster_width(
+            app,
+            vec![
+                Constraint::Percentage(20),
+                Constraint::Percentage(12),
+                Constraint::Percentage(21),
+                Constraint::Percentage(13),
+                Constraint::Percentage(13),
+                Constraint::Percentage(13),
+                Constraint::Percentage(8),
+            ],
+        ),
+    )
+    .header(header)
+    .block(block)
+    .row_highlight_style(theme::highlight());
+
+    f.render_stateful_widget(table, area, &mut app.nodes_state);
+}
+
+/// Row with a leading CLUSTER cell in the all-clusters view.
+fn with_cluster<'a>(app: &App, cluster: &Option<String>, mut cells: Vec<Cell<'a>>) -> Row<'a> {
+    if app.scope == Some(Scope::All) {
+        let cell = Cell::from(cluster.clone().unwrap_or_default()).style(theme::header());
+        cells.insert(0, cell);
+    }
+    Row::new(cells)
+}
+
+fn with_cluster_header<'a>(app: &App, mut titles: Vec<&'a str>) -> Vec<&'a str> {
+    if app.scope == Some(Scope::All) {
+        titles.insert(0, "CLUSTER");
+    }
+    titles
+}
+
+/// Column widths with room for the CLUSTER column when it is shown.
+fn with_cluster_width(app: &App, mut widths: Vec<Constraint>) -> Vec<Constraint> {
+    if app.scope == Some(Scope::All) {
+        let longest = app.clusters.iter().map(|c| c.len()).max().unwrap_or(0);
+        widths.insert(
+            0,
+            Constraint::Length(longest.max("CLUSTER".len()) as u16 + 1),
+        );
+    }
+    widths
+}
+
+/// Restart count, with the restarts of the last minutes when there were any.
+fn restarts_cell(p: &PodInfo, app: &App) -> Cell<'static> {
+    let Some(series) = app.history.pod(&p.namespace, &p.name) else {
+        return Cell::from(p.restarts.to_string()).style(theme::row_normal());
+    };
+    match series.recent_restarts() {
+        0 => Cell::from(p.restarts.to_string()).style(theme::row_normal()),
+        recent => {
+            let color = if series.is_flapping() {
+                theme::RED
+            } else {
+                theme::YELLOW
+            };
+            Cell::from(format!("{} (+{})", p.restarts, recent))
+                .style(ratatui::style::Style::default().fg(color))
+        }
+    }
+}
+
+/// Pod usage with its share of the requests and limits, e.g. "120m 60%/30%".
+/// Yellow above the request, red when close to the limit.
+fn usage_cell(
+    used: Option<i64>,
+    request: Option<i64>,
+    limit: Option<i64>,
+    format: fn(i64) -> String,
+) -> Cell<'static> {
+    let Some(used) = used else {
+        return Cell::from("-").style(ratatui::style::Style::default().fg(theme::TEXT_DIM));
+    };
+    let of_request = metrics::percent(used, request);
+    let of_limit = metrics::percent(used, limit);
+    let pct = |p: Option<i64>| p.map(|p| format!("{}%", p)).unwrap_or_else(|| "-".into());
+
+    let color = if of_limit.is_some_and(|p| p >= 90) {
+        theme::RED
+    } else if of_request.is_some_and(|p| p > 100) {
+        theme::YELLOW
+    } else {
+        theme::TEXT
+    };
+    Cell::from(format!(
+        "{} {}/{}",
+        format(used),
+        pct(of_request),
+        pct(of_limit)
+    ))
+    .style(ratatui::style::Style::default().fg(color))
+}
+
+/// Node usage with its share of the allocatable capacity, e.g. "1480m 37%".
+fn allocatable_cell(
+    used: Option<i64>,
+    allocatable: Option<i64>,
+    format: fn(i64) -> String,
+) -> Cell<'static> {
+    let Some(used) = used else {
+        return Cell::from("-").style(ratatui::style::Style::default().fg(theme::TEXT_DIM));
+    };
+    match metrics::percent(used, allocatable) {
+        Some(p) => {
+            let color = if p >= 90 {
+                theme::RED
+            } else if p >= 75 {
+                theme::YELLOW
+            } else {
+                theme::TEXT
+            };
+            Cell::from(format!("{} {}%", format(used), p))
+                .style(ratatui::style::Style::default().fg(color))
+        }
+        None => Cell::from(format(used)).style(theme::row_normal()),
+    }
+}
+
+/// Name cell, highlighted with a bullet when the row is marked.
+fn name_cell(name: &str, marked: bool) -> Cell<'static> {
+    if marked {
+        Cell::from(format!("● {}", name)).style(theme::header())
+    } else {
+        Cell::from(name.to_string()).style(theme::row_normal())
+    }
+}
+
+/// Panel title with the number of marked rows, if any.
+fn panel_title(name: &str, app: &App, pods: bool) -> String {
+    let marked = app
+        .selection
+        .iter()
+        .filter(|r| (r.kind == ResourceKind::Pod) == pods)
+        .count();
+    if marked > 0 {
+        format!(" {} [{} marked] ", name, marked)
+    } else {
+        format!(" {} ", name)
+    }
+}
+
+fn render_footer(f: &mut Frame, area: Rect, app: &App) {
+    if app.filter_editing {
+        let prompt = Line::from(vec![
+            Span::styled(" /", ratatui::style::Style::default().fg(theme::ACCENT)),
+            Span::styled(format!("{}_", app.filter), theme::row_normal()),
+            Span::styled(
+                "  Enter apply  Esc clear",
+                ratatui::style::Style::default().fg(theme::TEXT_DIM),
+            ),
+        ]);
+        f.render_widget(Paragraph::new(prompt), area);
+        return;
+    }
+
+    let mut keys = Line::from(vec![
         Span::styled(" Tab", ratatui::style::Style::default().fg(theme::ACCENT)),
         Span::styled(
             " switch  ",
@@ -149,17 +561,111 @@ fn render_footer(f: &mut Frame, area: Rect) {
             " scroll  ",
             ratatui::style::Style::default().fg(theme::TEXT_DIM),
         ),
+        Span::styled("/", ratatui::style::Style::default().fg(theme::ACCENT)),
+        Span::styled(
+            " filter  ",
+            ratatui::style::Style::default().fg(theme::TEXT_DIM),
+        ),
+        Span::styled(
+            "space/*",
+            ratatui::style::Style::default().fg(theme::ACCENT),
+        ),
+        Span::styled(
+            " mark  ",
+            ratatui::style::Style::default().fg(theme::TEXT_DIM),
+        ),
+        Span::styled("s", ratatui::style::Style::default().fg(theme::ACCENT)),
+        Span::styled(
+            " scale  ",
+            ratatui::style::Style::default().fg(theme::TEXT_DIM),
+        ),
+        Span::styled("R", ratatui::style::Style::default().fg(theme::ACCENT)),
+        Span::styled(
+            " restart  ",
+            ratatui::style::Style::default().fg(theme::TEXT_DIM),
+        ),
+        Span::styled("o", ratatui::style::Style::default().fg(theme::ACCENT)),
+        Span::styled(
+            " rollout  ",
+            ratatui::style::Style::default().fg(theme::TEXT_DIM),
+        ),
+        Span::styled("d", ratatui::style::Style::default().fg(theme::ACCENT)),
+        Span::styled(
+            " delete  ",
+            ratatui::style::Style::default().fg(theme::TEXT_DIM),
+        ),
+        Span::styled("L", ratatui::style::Style::default().fg(theme::ACCENT)),
+        Span::styled(
+            " label  ",
+            ratatui::style::Style::default().fg(theme::TEXT_DIM),
+        ),
+        Span::styled("l", ratatui::style::Style::default().fg(theme::ACCENT)),
+        Span::styled(
+            " logs  ",
+            ratatui::style::Style::default().fg(theme::TEXT_DIM),
+        ),
+        Span::styled("Enter", ratatui::style::Style::default().fg(theme::ACCENT)),
+        Span::styled(
+            " usage  ",
+            ratatui::style::Style::default().fg(theme::TEXT_DIM),
+        ),
         Span::styled("r", ratatui::style::Style::default().fg(theme::ACCENT)),
         Span::styled(
             " refresh  ",
             ratatui::style::Style::default().fg(theme::TEXT_DIM),
         ),
-        Span::styled("q", ratatui::style::Style::default().fg(theme::ACCENT)),
+        Span::styled("!", ratatui::style::Style::default().fg(theme::ACCENT)),
         Span::styled(
-            " quit",
+            " problems  ",
             ratatui::style::Style::default().fg(theme::TEXT_DIM),
         ),
     ]);
+    if !app.clusters.is_empty() {
+        keys.push_span(Span::styled(
+            "c",
+            ratatui::style::Style::default().fg(theme::ACCENT),
+        ));
+        keys.push_span(Span::styled(
+            " cluster  ",
+            ratatui::style::Style::default().fg(theme::TEXT_DIM),
+        ));
+    }
+    if app.contexts.len() > 1 {
+        keys.push_span(Span::styled(
+            "x",
+            ratatui::style::Style::default().fg(theme::ACCENT),
+        ));
+        keys.push_span(Span::styled(
+            " context  ",
+            ratatui::style::Style::default().fg(theme::TEXT_DIM),
+        ));
+    }
+    keys.push_span(Span::styled(
+        "q",
+        ratatui::style::Style::default().fg(theme::ACCENT),
+    ));
+    keys.push_span(Span::styled(
+        " quit",
+        ratatui::style::Style::default().fg(theme::TEXT_DIM),
+    ));
+    if app.problems_only {
+        keys.push_span(Span::styled(
+            "  problems only",
+            ratatui::style::Style::default().fg(theme::ACCENT),
+        ));
+    }
+    if let Some(namespace) = &app.namespace {
+        keys.push_span(Span::styled(
+            format!("  namespace: {}", namespace),
+            ratatui::style::Style::default().fg(theme::ACCENT),
+        ));
+    }
+    if !app.filter.is_empty() {
+        keys.push_span(Span::styled(
+            format!("  filter: {}", app.filter),
+            ratatui::style::Style::default().fg(theme::ACCENT),
+        ));
+    }
 
     let footer = Paragraph::new(keys);
     f.render_widget(footer, area);
@@ -0,0 +1,269 @@
+use std::collections::VecDeque;
+
+use ratatui::Frame;
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::Style;
+use ratatui::symbols::Marker;
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{
+    Axis, Block, Borders, Chart, Clear, Dataset, GraphType, Paragraph, Sparkline,
+};
+
+use crate::app::DetailView;
+use crate::history::{SAMPLE_INTERVAL, Series};
+use crate::k8s::metrics;
+use crate::ui::dialog::centered;
+use crate::ui::theme;
+
+pub fn render(f: &mut Frame, view: &DetailView) {
+    let outer = f.area();
+    let area = centered(outer, outer.width * 8 / 10, outer.height * 8 / 10);
+
+    let title = match view {
+        DetailView::Pod { pod, .. } => format!(" Pod {}/{} ", pod.namespace, pod.name),
+        DetailView::Node { node, .. } => format!(" Node {} ", node.name),
+    };
+    let block = Block::default()
+        .title(Span::styled(title, theme::header()))
+        .borders(Borders::ALL)
+        .border_style(theme::border(true));
+    let inner = block.inner(area);
+
+    f.render_widget(Clear, area);
+    f.render_widget(block, area);
+
+    match view {
+        DetailView::Pod { pod, series } => {
+            let chunks = Layout::vertical([
+                Constraint::Length(2),
+                Constraint::Fill(1),
+                Constraint::Fill(1),
+                Constraint::Fill(1),
+                Constraint::Length(1),
+            ])
+            .split(inner);
+
+            let restarts = if series.is_flapping() {
+                Span::styled(
+                    format!(
+                        "{} (+{} in {}) flapping",
+                        pod.restarts,
+                        series.recent_restarts(),
+                        window(series)
+                    ),
+                    Style::default().fg(theme::RED),
+                )
+            } else {
+                Span::styled(pod.restarts.to_string(), theme::row_normal())
+            };
+            let info = Line::from(vec![
+                Span::styled("Status ", theme::table_header()),
+                Span::styled(pod.status.clone(), theme::status_style(&pod.status)),
+                Span::styled("  Restarts ", theme::table_header()),
+                restarts,
+                Span::styled("  Age ", theme::table_header()),
+                Span::styled(pod.age.clone(), theme::row_normal()),
+            ]);
+            f.render_widget(Paragraph::new(info), chunks[0]);
+
+            let cpu = format!(
+                "CPU  request {}  limit {}",
+                or_dash(pod.cpu_request, metrics::format_cpu),
+                or_dash(pod.cpu_limit, metrics::format_cpu)
+            );
+            let mem = format!(
+                "Memory  request {}  limit {}",
+                or_dash(pod.mem_request, metrics::format_memory),
+                or_dash(pod.mem_limit, metrics::format_memory)
+            );
+            render_sparkline(
+                f,
+                chunks[1],
+                &cpu,
+                &series.cpu,
+                pod.cpu_limit,
+                metrics::format_cpu,
+            );
+            render_sparkline(
+                f,
+                chunks[2],
+                &mem,
+                &series.memory,
+                pod.mem_limit,
+                metrics::format_memory,
+            );
+            render_restarts(f, chunks[3], series);
+            render_keys(f, chunks[4], series);
+        }
+        DetailView::Node { node, series } => {
+            let chunks = Layout::vertical([
+                Constraint::Length(2),
+                Constraint::Fill(1),
+                Constraint::Fill(1),
+                Constraint::Length(1),
+            ])
+            .split(inner);
+
+            let status = if node.status.starts_with("Ready") {
+                "Running"
+            } else {
+                "Failed"
+            };
+            let info = Line::from(vec![
+                Span::styled("Status ", theme::table_header()),
+                Span::styled(node.status.clone(), theme::status_style(status)),
+                Span::styled("  Roles ", theme::table_header()),
+                Span::styled(node.roles.clone(), theme::row_normal()),
+                Span::styled("  Version ", theme::table_header()),
+                Span::styled(node.version.clone(), theme::row_normal()),
+            ]);
+            f.render_widget(Paragraph::new(info), chunks[0]);
+
+            let cpu = format!(
+                "CPU  allocatable {}",
+                or_dash(node.cpu_allocatable, metrics::format_cpu)
+            );
+            let mem = format!(
+                "Memory  allocatable {}",
+                or_dash(node.mem_allocatable, metrics::format_memory)
+            );
+            render_sparkline(
+                f,
+                chunks[1],
+                &cpu,
+                &series.cpu,
+                node.cpu_allocatable,
+                metrics::format_cpu,
+            );
+            render_sparkline(
+                f,
+                chunks[2],
+                &mem,
+                &series.memory,
+                node.mem_allocatable,
+                metrics::format_memory,
+            );
+            render_keys(f, chunks[3], series);
+        }
+    }
+}
+
+/// Usage over time, scaled to `ceiling` (limit or allocatable) when known.
+fn render_sparkline(
+    f: &mut Frame,
+    area: Rect,
+    label: &str,
+    samples: &VecDeque<Option<u64>>,
+    ceiling: Option<i64>,
+    format: fn(i64) -> String,
+) {
+    let current = samples.back().copied().flatten();
+    let peak = samples.iter().flatten().max().copied();
+    let title = Line::from(vec![
+        Span::styled(format!(" {} ", label), theme::table_header()),
+        Span::styled(
+            format!(
+                " now {}  max {} ",
+                or_dash(current.map(|v| v as i64), format),
+                or_dash(peak.map(|v| v as i64), format)
+            ),
+            theme::row_normal(),
+        ),
+    ]);
+    let block = Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .border_style(theme::border(false));
+
+    // the sparkline draws from the left, keep the newest samples that fit
+    let width = block.inner(area).width as usize;
+    let data: Vec<Option<u64>> = samples
+        .iter()
+        .skip(samples.len().saturating_sub(width))
+        .copied()
+        .collect();
+
+    let mut sparkline = Sparkline::default()
+        .block(block)
+        .data(data)
+        .style(Style::default().fg(theme::ACCENT))
+        .absent_value_symbol("·")
+        .absent_value_style(Style::default().fg(theme::TEXT_DIM));
+    if let Some(ceiling) = ceiling {
+        sparkline = sparkline.max((ceiling as u64).max(peak.unwrap_or(0)));
+    }
+    f.render_widget(sparkline, area);
+}
+
+/// Restart count over the window as a step chart.
+fn render_restarts(f: &mut Frame, area: Rect, series: &Series) {
+    let secs = SAMPLE_INTERVAL.as_secs_f64();
+    let start = -((series.restarts.len().saturating_sub(1)) as f64) * secs;
+    let points: Vec<(f64, f64)> = series
+        .restarts
+        .iter()
+        .enumerate()
+        .map(|(i, r)| (start + i as f64 * secs, *r as f64))
+        .collect();
+
+    let low = series.restarts.iter().min().copied().unwrap_or(0) as f64;
+    let high = series.restarts.iter().max().copied().unwrap_or(0) as f64;
+    let color = if series.is_flapping() {
+        theme::RED
+    } else {
+        theme::GREEN
+    };
+
+    let dataset = Dataset::default()
+        .marker(Marker::Braille)
+        .graph_type(GraphType::Line)
+        .style(Style::default().fg(color))
+        .data(&points);
+
+    let chart = Chart::new(vec![dataset])
+        .block(
+            Block::default()
+                .title(Span::styled(" Restarts ", theme::table_header()))
+                .borders(Borders::ALL)
+                .border_style(theme::border(false)),
+        )
+        .x_axis(
+            Axis::default()
+                .style(Style::default().fg(theme::TEXT_DIM))
+                .bounds([start.min(-secs), 0.0])
+                .labels([format!("-{}", window(series)), "now".into()]),
+        )
+        .y_axis(
+            Axis::default()
+                .style(Style::default().fg(theme::TEXT_DIM))
+                .bounds([low, high + 1.0])
+                .labels([format!("{}", low), format!("{}", high + 1.0)]),
+        );
+    f.render_widget(chart, area);
+}
+
+fn render_keys(f: &mut Frame, area: Rect, series: &Series) {
+    let keys = Paragraph::new(Line::from(vec![
+        Span::styled(" Esc", Style::default().fg(theme::ACCENT)),
+        Span::styled(" close  ", Style::default().fg(theme::TEXT_DIM)),
+        Span::styled(
+            format!("{} samples over {}", series.cpu.len(), window(series)),
+            Style::default().fg(theme::TEXT_DIM),
+        ),
+    ]));
+    f.render_widget(keys, area);
+}
+
+/// Covered time span, e.g. "10m" or "45s".
+fn window(series: &Series) -> String {
+    let secs = series.span().as_secs();
+    if secs >= 60 {
+        format!("{}m", secs.div_ceil(60))
+    } else {
+        format!("{}s", secs)
+    }
+}
+
+fn or_dash(value: Option<i64>, format: fn(i64) -> String) -> String {
+    value.map(format).unwrap_or_else(|| "-".into())
+}
@@ -0,0 +1,399 @@
+use ratatui::Frame;
+use ratatui::layout::{Constraint, Flex, Layout, Rect};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Clear, Paragraph, Row, Table, TableState};
+
+use crate::app::{
+    ClusterPicker, ContextPicker, DeleteDialog, Dialog, HostKeyDialog, LabelDialog, ResultsView,
+    ScaleDialog, Scope, Step, Switching,
+};
+use crate::k8s::ResourceRef;
+use crate::ui::{detail, logs, rollout, theme};
+
+/// Resources listed by name before collapsing into "and N more".
+const MAX_LISTED: usize = 8;
+const SPINNER: [&str; 10] = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+
+pub fn render(f: &mut Frame, dialog: &mut Dialog) {
+    match dialog {
+        Dialog::Scale(d) => render_scale(f, d),
+        Dialog::Rollout(view) => rollout::render(f, view),
+        Dialog::Delete(d) => render_delete(f, d),
+        Dialog::Label(d) => render_label(f, d),
+        Dialog::Logs(view) => logs::render(f, view),
+        Dialog::Results(view) => render_results(f, view),
+        Dialog::Detail(view) => detail::render(f, view),
+        Dialog::Clusters(picker) => render_clusters(f, picker),
+        Dialog::Contexts(picker) => render_contexts(f, picker),
+        Dialog::HostKey(d) => render_host_key(f, d),
+    }
+}
+
+fn render_scale(f: &mut Frame, d: &ScaleDialog) {
+    let area = centered(f.area(), 50, 8);
+
+    let lines = vec![
+        Line::from(vec![
+            Span::styled(format!("{} ", d.kind.as_str()), theme::table_header()),
+            Span::styled(format!("{}/{}", d.namespace, d.name), theme::row_normal()),
+        ]),
+        Line::default(),
+        Line::from(vec![
+            Span::styled("Current replicas: ", theme::table_header()),
+            Span::styled(d.current.to_string(), theme::row_normal()),
+        ]),
+        Line::from(vec![
+            Span::styled("New replicas:     ", theme::table_header()),
+            Span::styled(format!("{}_", d.input), theme::header()),
+        ]),
+        Line::default(),
+        Line::from(Span::styled(
+            "Enter apply  ↑/↓ adjust  Esc cancel",
+            ratatui::style::Style::default().fg(theme::TEXT_DIM),
+        )),
+    ];
+
+    let block = Block::default()
+        .title(Span::styled(" Scale ", theme::header()))
+        .borders(Borders::ALL)
+        .border_style(theme::border(true));
+
+    f.render_widget(Clear, area);
+    f.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+/// One line per target, truncated after [`MAX_LISTED`].
+fn target_lines(targets: &[ResourceRef]) -> Vec<Line<'static>> {
+    let mut lines: Vec<Line> = targets
+        .iter()
+        .take(MAX_LISTED)
+        .map(|t| {
+            Line::from(vec![
+                Span::styled(format!("  {} ", t.kind.as_str()), theme::table_header()),
+                Span::styled(t.name.clone(), theme::header()),
+                Span::styled(" in ", theme::table_header()),
+                Span::styled(t.namespace.clone(), theme::row_normal()),
+            ])
+        })
+        .collect();
+    if targets.len() > MAX_LISTED {
+        lines.push(Line::from(Span::styled(
+            format!("  ... and {} more", targets.len() - MAX_LISTED),
+            theme::table_header(),
+        )));
+    }
+    lines
+}
+
+fn render_delete(f: &mut Frame, d: &DeleteDialog) {
+    let listed = d.targets.len().min(MAX_LISTED + 1) as u16;
+    let area = centered(f.area(), 64, 8 + listed);
+
+    let grace = if d.force {
+        Span::styled("0 (forced)", theme::row_normal())
+    } else if d.grace.is_empty() {
+        Span::styled("default_", theme::header())
+    } else {
+        Span::styled(format!("{}s_", d.grace), theme::header())
+    };
+    let force = if d.force {
+        Span::styled("on", ratatui::style::Style::default().fg(theme::RED))
+    } else {
+        Span::styled("off", theme::row_normal())
+    };
+
+    let mut lines = vec![Line::from(Span::styled(
+        format!(
+            "Delete {}?",
+            if d.targets.len() == 1 {
+                "this resource".to_string()
+            } else {
+                format!("{} resources", d.targets.len())
+            }
+        ),
+        theme::table_header(),
+    ))];
+    lines.extend(target_lines(&d.targets));
+    lines.extend([
+        Line::default(),
+        Line::from(vec![
+            Span::styled("Grace period: ", theme::table_header()),
+            grace,
+        ]),
+        Line::from(vec![
+            Span::styled("Force:        ", theme::table_header()),
+            force,
+        ]),
+        Line::default(),
+        Line::from(Span::styled(
+            "y confirm  0-9 grace period  f force  n/Esc cancel",
+            ratatui::style::Style::default().fg(theme::TEXT_DIM),
+        )),
+    ]);
+
+    let block = Block::default()
+        .title(Span::styled(" Delete ", theme::header()))
+        .borders(Borders::ALL)
+        .border_style(ratatui::style::Style::default().fg(theme::RED));
+
+    f.render_widget(Clear, area);
+    f.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+fn render_label(f: &mut Frame, d: &LabelDialog) {
+    let listed = d.targets.len().min(MAX_LISTED + 1) as u16;
+    let area = centered(f.area(), 64, 7 + listed);
+
+    let mut lines = vec![Line::from(Span::styled("Label", theme::table_header()))];
+    lines.extend(target_lines(&d.targets));
+    lines.extend([
+        Line::default(),
+        Line::from(vec![
+            Span::styled("Label: ", theme::table_header()),
+            Span::styled(format!("{}_", d.input), theme::header()),
+        ]),
+        Line::from(Span::styled(
+            "key=value sets, key- removes  Enter apply  Esc cancel",
+            ratatui::style::Style::default().fg(theme::TEXT_DIM),
+        )),
+    ]);
+
+    let block = Block::default()
+        .title(Span::styled(" Label ", theme::header()))
+        .borders(Borders::ALL)
+        .border_style(theme::border(true));
+
+    f.render_widget(Clear, area);
+    f.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+fn render_results(f: &mut Frame, view: &mut ResultsView) {
+    let area = centered(
+        f.area(),
+        f.area().width * 4 / 5,
+        (view.results.len() as u16 + 4).min(f.area().height * 4 / 5),
+    );
+
+    let lines: Vec<Line> = view
+        .results
+        .iter()
+        .map(|(target, res)| match res {
+            Ok(()) => Line::from(vec![
+                Span::styled(" ✓ ", ratatui::style::Style::default().fg(theme::GREEN)),
+                Span::styled(target.to_string(), theme::row_normal()),
+            ]),
+            Err(e) => Line::from(vec![
+                Span::styled(" ✗ ", ratatui::style::Style::default().fg(theme::RED)),
+                Span::styled(target.to_string(), theme::row_normal()),
+                Span::styled(
+                    format!("  {}", e),
+                    ratatui::style::Style::default().fg(theme::RED),
+                ),
+            ]),
+        })
+        .collect();
+
+    let block = Block::default()
+        .title(Span::styled(format!(" {} ", view.title), theme::header()))
+        .borders(Borders::ALL)
+        .border_style(theme::border(true));
+    let inner = block.inner(area);
+    f.render_widget(Clear, area);
+    f.render_widget(block, area);
+
+    let chunks = Layout::vertical([
+        Constraint::Min(1),
+        Constraint::Length(1),
+        Constraint::Length(1),
+    ])
+    .split(inner);
+    // stop once the last result is at the bottom
+    let max = (lines.len() as u16).saturating_sub(chunks[0].height);
+    view.scroll = view.scroll.min(max);
+    f.render_widget(Paragraph::new(lines).scroll((view.scroll, 0)), chunks[0]);
+
+    let hint = if max > 0 {
+        " j/k scroll  Esc close"
+    } else {
+        " Esc close"
+    };
+    f.render_widget(
+        Paragraph::new(Span::styled(
+            hint,
+            ratatui::style::Style::default().fg(theme::TEXT_DIM),
+        )),
+        chunks[2],
+    );
+}
+
+fn render_clusters(f: &mut Frame, picker: &mut ClusterPicker) {
+    let entries = picker
+        .entries
+        .iter()
+        .map(|entry| {
+            let style = match entry {
+                Scope::All => theme::header(),
+                Scope::Cluster(_) => theme::row_normal(),
+            };
+            let current = picker.current.as_ref() == Some(entry);
+            (current, Span::styled(entry.to_string(), style))
+        })
+        .collect();
+    render_picker(f, " Clusters ", entries, &mut picker.state);
+}
+
+fn render_contexts(f: &mut Frame, picker: &mut ContextPicker) {
+    let entries = picker
+        .names
+        .iter()
+        .map(|name| {
+            let current = picker.current.as_ref() == Some(name);
+            (current, Span::styled(name.clone(), theme::row_normal()))
+        })
+        .collect();
+    render_picker(f, " Contexts ", entries, &mut picker.state);
+}
+
+/// List to connect to one of `entries`, the current one marked with a dot.
+fn render_picker(
+    f: &mut Frame,
+    title: &str,
+    entries: Vec<(bool, Span<'static>)>,
+    state: &mut TableState,
+) {
+    let area = centered(f.area(), 48, entries.len() as u16 + 2);
+
+    let rows: Vec<Row> = entries
+        .into_iter()
+        .map(|(current, entry)| {
+            Row::new(vec![
+                Span::styled(
+                    if current { "●" } else { " " },
+                    ratatui::style::Style::default().fg(theme::GREEN),
+                ),
+                entry,
+            ])
+        })
+        .collect();
+
+    let block = Block::default()
+        .title(Span::styled(title.to_string(), theme::header()))
+        .title_bottom(Span::styled(
+            " Enter connect  Esc close ",
+            ratatui::style::Style::default().fg(theme::TEXT_DIM),
+        ))
+        .borders(Borders::ALL)
+        .border_style(theme::border(true));
+    let table = Table::new(rows, [Constraint::Length(2), Constraint::Fill(1)])
+        .block(block)
+        .row_highlight_style(theme::highlight());
+
+    f.render_widget(Clear, area);
+    f.render_stateful_widget(table, area, state);
+}
+
+/// The prompt ssh shows for an unknown host key, answered in the dashboard.
+fn render_host_key(f: &mut Frame, d: &HostKeyDialog) {
+    let key = &d.key;
+    let host = match key.port {
+        22 => key.host.clone(),
+        port => format!("{}:{}", key.host, port),
+    };
+    let lines = vec![
+        Line::from(vec![
+            Span::styled("The authenticity of host ", theme::table_header()),
+            Span::styled(host, theme::header()),
+            Span::styled(" can't be established.", theme::table_header()),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                format!("{} key fingerprint is ", key.key.algorithm()),
+                theme::table_header(),
+            ),
+            Span::styled(key.fingerprint(), theme::row_normal()),
+        ]),
+        Line::default(),
+        Line::from(Span::styled(
+            format!("Trust it and add it to {}?", key.known_hosts.display()),
+            theme::table_header(),
+        )),
+        Line::default(),
+        Line::from(Span::styled(
+            "y trust and connect  n/Esc cancel",
+            ratatui::style::Style::default().fg(theme::TEXT_DIM),
+        )),
+    ];
+
+    let block = Block::default()
+        .title(Span::styled(" Unknown host key ", theme::header()))
+        .borders(Borders::ALL)
+        .border_style(theme::border(true));
+
+    let width = lines.iter().map(Line::width).max().unwrap_or(0) as u16 + 4;
+    let area = centered(f.area(), width, lines.len() as u16 + 2);
+    f.render_widget(Clear, area);
+    f.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+/// Progress of connecting to other clusters, one line per cluster, drawn over
+/// everything else.
+pub fn render_switching(f: &mut Frame, switching: &Switching) {
+    let area = centered(f.area(), 80, switching.connects.len() as u16 + 4);
+
+    let frame = (switching.started.elapsed().as_millis() / 100) as usize % SPINNER.len();
+    let width = switching
+        .connects
+        .iter()
+        .map(|c| c.name.len())
+        .max()
+        .unwrap_or(0);
+    let mut lines: Vec<Line> = switching
+        .connects
+        .iter()
+        .map(|connect| {
+            let (mark, color, text) = match &*connect.step.borrow() {
+                Step::Running(step) => (SPINNER[frame], theme::ACCENT, step.clone()),
+                Step::Done => ("✓", theme::GREEN, "Connected".to_string()),
+                Step::Failed(error) => ("✗", theme::RED, error.clone()),
+            };
+            Line::from(vec![
+                Span::styled(
+                    format!(" {} ", mark),
+                    ratatui::style::Style::default().fg(color),
+                ),
+                Span::styled(
+                    format!("{:<width$}  ", connect.name, width = width),
+                    theme::header(),
+                ),
+                Span::styled(text, theme::row_normal()),
+            ])
+        })
+        .collect();
+    lines.push(Line::default());
+    lines.push(Line::from(Span::styled(
+        " q quit",
+        ratatui::style::Style::default().fg(theme::TEXT_DIM),
+    )));
+
+    let block = Block::default()
+        .title(Span::styled(
+            format!(" Connecting to {} ", switching.target()),
+            theme::header(),
+        ))
+        .borders(Borders::ALL)
+        .border_style(theme::border(true));
+
+    f.render_widget(Clear, area);
+    f.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+/// Fixed-size rect centered in `area`, clamped to fit.
+pub fn centered(area: Rect, width: u16, height: u16) -> Rect {
+    let [area] = Layout::horizontal([Constraint::Length(width.min(area.width))])
+        .flex(Flex::Center)
+        .areas(area);
+    let [area] = Layout::vertical([Constraint::Length(height.min(area.height))])
+        .flex(Flex::Center)
+        .areas(area);
+    area
+}
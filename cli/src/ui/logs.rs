@@ -0,0 +1,76 @@
+use ratatui::Frame;
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::Style;
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Clear, Paragraph};
+
+use crate::app::LogView;
+use crate::ui::dialog::centered;
+use crate::ui::theme;
+
+pub fn render(f: &mut Frame, view: &LogView) {
+    let outer = f.area();
+    let area = centered(outer, outer.width * 9 / 10, outer.height * 9 / 10);
+
+    // a single pod's container is named once it has more than one
+    let containers = match view.containers.as_slice() {
+        [containers] if containers.len() > 1 => containers.as_slice(),
+        _ => &[],
+    };
+    let title = match (view.pods.as_slice(), containers.get(view.container)) {
+        ([pod], Some(container)) => {
+            format!(" Logs {}/{} [{}] ", pod.namespace, pod.name, container)
+        }
+        ([pod], None) => format!(" Logs {}/{} ", pod.namespace, pod.name),
+        (pods, _) => format!(" Logs ({} pods) ", pods.len()),
+    };
+    let block = Block::default()
+        .title(Span::styled(title, theme::header()))
+        .borders(Borders::ALL)
+        .border_style(theme::border(true));
+    let inner = block.inner(area);
+
+    f.render_widget(Clear, area);
+    f.render_widget(block, area);
+
+    let chunks = Layout::vertical([Constraint::Min(1), Constraint::Length(1)]).split(inner);
+
+    let lines: Vec<Line> = view
+        .lines
+        .iter()
+        .map(|l| Line::from(Span::styled(l.clone(), theme::row_normal())))
+        .collect();
+
+    // anchor to the newest line, offset by how far the user scrolled back
+    let height = chunks[0].height;
+    let scroll = (view.lines.len() as u16)
+        .saturating_sub(height)
+        .saturating_sub(view.scroll_back);
+    f.render_widget(Paragraph::new(lines).scroll((scroll, 0)), chunks[0]);
+
+    let follow = if view.scroll_back == 0 {
+        "following"
+    } else {
+        "paused"
+    };
+    let mut keys = vec![
+        Span::styled(" j/k", Style::default().fg(theme::ACCENT)),
+        Span::styled(" scroll  ", Style::default().fg(theme::TEXT_DIM)),
+        Span::styled("f", Style::default().fg(theme::ACCENT)),
+        Span::styled(" follow  ", Style::default().fg(theme::TEXT_DIM)),
+    ];
+    if !containers.is_empty() {
+        keys.push(Span::styled("c", Style::default().fg(theme::ACCENT)));
+        keys.push(Span::styled(
+            format!(" container ({}/{})  ", view.container + 1, containers.len()),
+            Style::default().fg(theme::TEXT_DIM),
+        ));
+    }
+    keys.extend([
+        Span::styled("Esc", Style::default().fg(theme::ACCENT)),
+        Span::styled(" close  ", Style::default().fg(theme::TEXT_DIM)),
+        Span::styled(follow, Style::default().fg(theme::TEXT_DIM)),
+    ]);
+    let keys = Paragraph::new(Line::from(keys));
+    f.render_widget(keys, chunks[1]);
+}
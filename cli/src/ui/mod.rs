@@ -1,4 +1,8 @@
 pub mod dashboard;
+pub mod detail;
+pub mod dialog;
+pub mod logs;
+pub mod rollout;
 pub mod theme;
 
 use ratatui::Frame;
@@ -7,4 +11,11 @@ use crate::app::App;
 
 pub fn draw(f: &mut Frame, app: &mut App) {
     dashboard::render(f, app);
+
+    if let Some(dialog) = &mut app.dialog {
+        dialog::render(f, dialog);
+    }
+    if let Some(switching) = &app.switching {
+        dialog::render_switching(f, switching);
+    }
 }
@@ -0,0 +1,220 @@
+use ratatui::Frame;
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::Style;
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Cell, Clear, LineGauge, Paragraph, Row, Table};
+
+use crate::app::{RevisionDiff, RolloutView};
+use crate::k8s::rollout::DiffTag;
+use crate::ui::dialog::centered;
+use crate::ui::theme;
+
+pub fn render(f: &mut Frame, view: &mut RolloutView) {
+    let outer = f.area();
+    let area = centered(outer, outer.width * 4 / 5, outer.height * 4 / 5);
+
+    let block = Block::default()
+        .title(Span::styled(
+            format!(" Rollout {}/{} ", view.namespace, view.name),
+            theme::header(),
+        ))
+        .borders(Borders::ALL)
+        .border_style(theme::border(true));
+    let inner = block.inner(area);
+
+    f.render_widget(Clear, area);
+    f.render_widget(block, area);
+
+    let chunks = Layout::vertical([
+        Constraint::Length(4), // status
+        Constraint::Min(3),    // history
+        Constraint::Length(1), // keys
+    ])
+    .split(inner);
+
+    render_status(f, chunks[0], view);
+
+    if let Some(diff) = &view.diff {
+        render_diff(f, chunks[1], diff);
+        let keys = Paragraph::new(Line::from(vec![
+            Span::styled(" j/k", Style::default().fg(theme::ACCENT)),
+            Span::styled(" scroll  ", Style::default().fg(theme::TEXT_DIM)),
+            Span::styled("Esc", Style::default().fg(theme::ACCENT)),
+            Span::styled(" back to history", Style::default().fg(theme::TEXT_DIM)),
+        ]));
+        f.render_widget(keys, chunks[2]);
+        return;
+    }
+
+    render_history(f, chunks[1], view);
+
+    let keys = Paragraph::new(Line::from(vec![
+        Span::styled(" j/k", Style::default().fg(theme::ACCENT)),
+        Span::styled(" select  ", Style::default().fg(theme::TEXT_DIM)),
+        Span::styled("space", Style::default().fg(theme::ACCENT)),
+        Span::styled(" mark  ", Style::default().fg(theme::TEXT_DIM)),
+        Span::styled("d", Style::default().fg(theme::ACCENT)),
+        Span::styled(" diff  ", Style::default().fg(theme::TEXT_DIM)),
+        Span::styled("u", Style::default().fg(theme::ACCENT)),
+        Span::styled(
+            " roll back to revision  ",
+            Style::default().fg(theme::TEXT_DIM),
+        ),
+        Span::styled("R", Style::default().fg(theme::ACCENT)),
+        Span::styled(" restart  ", Style::default().fg(theme::TEXT_DIM)),
+        Span::styled("Esc", Style::default().fg(theme::ACCENT)),
+        Span::styled(" close", Style::default().fg(theme::TEXT_DIM)),
+    ]));
+    f.render_widget(keys, chunks[2]);
+}
+
+fn render_status(f: &mut Frame, area: ratatui::layout::Rect, view: &RolloutView) {
+    let Some(status) = &view.status else {
+        f.render_widget(
+            Paragraph::new(Span::styled(
+                " Loading...",
+                Style::default().fg(theme::TEXT_DIM),
+            )),
+            area,
+        );
+        return;
+    };
+
+    let color = if status.failed {
+        theme::RED
+    } else if status.done {
+        theme::GREEN
+    } else {
+        theme::YELLOW
+    };
+
+    let rows = Layout::vertical([
+        Constraint::Length(1),
+        Constraint::Length(1),
+        Constraint::Length(1),
+    ])
+    .split(area);
+
+    f.render_widget(
+        Paragraph::new(Span::styled(
+            format!(" {}", status.message),
+            Style::default().fg(color),
+        )),
+        rows[0],
+    );
+
+    let counts = Line::from(vec![
+        Span::styled(" desired ", theme::table_header()),
+        Span::styled(status.desired.to_string(), theme::row_normal()),
+        Span::styled("  updated ", theme::table_header()),
+        Span::styled(status.updated.to_string(), theme::row_normal()),
+        Span::styled("  ready ", theme::table_header()),
+        Span::styled(status.ready.to_string(), theme::row_normal()),
+        Span::styled("  available ", theme::table_header()),
+        Span::styled(status.available.to_string(), theme::row_normal()),
+    ]);
+    f.render_widget(Paragraph::new(counts), rows[1]);
+
+    let ratio = if status.desired > 0 {
+        (status.available.min(status.desired) as f64) / status.desired as f64
+    } else {
+        1.0
+    };
+    let gauge = LineGauge::default()
+        .filled_style(Style::default().fg(color))
+        .unfilled_style(Style::default().fg(theme::BORDER))
+        .ratio(ratio);
+    f.render_widget(gauge, rows[2]);
+}
+
+fn render_history(f: &mut Frame, area: ratatui::layout::Rect, view: &mut RolloutView) {
+    // history is sorted newest first, so the first entry is the live revision
+    let current = view.history.first().map(|r| r.revision);
+
+    let rows: Vec<Row> = view
+        .history
+        .iter()
+        .map(|r| {
+            let marker = if Some(r.revision) == current {
+                "*"
+            } else {
+                " "
+            };
+            Row::new(vec![
+                Cell::from(format!("{}{}", marker, r.revision)).style(theme::row_normal()),
+                Cell::from(r.replica_set.clone()).style(theme::row_normal()),
+                Cell::from(format!("{}/{}", r.ready, r.replicas)).style(theme::row_normal()),
+                Cell::from(r.images.join(", ")).style(Style::default().fg(theme::TEXT_DIM)),
+                Cell::from(r.change_cause.clone().unwrap_or_else(|| "<none>".into()))
+                    .style(Style::default().fg(theme::TEXT_DIM)),
+                Cell::from(r.age.clone()).style(Style::default().fg(theme::TEXT_DIM)),
+            ])
+        })
+        .collect();
+
+    let header = Row::new(vec![
+        "REVISION",
+        "REPLICASET",
+        "READY",
+        "IMAGES",
+        "CHANGE-CAUSE",
+        "AGE",
+    ])
+    .style(theme::table_header())
+    .bottom_margin(1);
+
+    let block = Block::default()
+        .title(Span::styled(" History ", theme::header()))
+        .borders(Borders::TOP)
+        .border_style(Style::default().fg(theme::BORDER));
+
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Length(10),
+            Constraint::Percentage(25),
+            Constraint::Length(6),
+            Constraint::Percentage(30),
+            Constraint::Percentage(30),
+            Constraint::Length(5),
+        ],
+    )
+    .header(header)
+    .block(block)
+    .row_highlight_style(theme::highlight());
+
+    f.render_stateful_widget(table, area, &mut view.history_state);
+}
+
+fn render_diff(f: &mut Frame, area: ratatui::layout::Rect, diff: &RevisionDiff) {
+    let lines: Vec<Line> = if diff.lines.is_empty() {
+        vec![Line::from(Span::styled(
+            " Pod templates are identical",
+            Style::default().fg(theme::TEXT_DIM),
+        ))]
+    } else {
+        diff.lines
+            .iter()
+            .map(|l| {
+                let (prefix, style) = match l.tag {
+                    DiffTag::Hunk => ("", Style::default().fg(theme::ACCENT)),
+                    DiffTag::Added => ("+", Style::default().fg(theme::GREEN)),
+                    DiffTag::Removed => ("-", Style::default().fg(theme::RED)),
+                    DiffTag::Context => (" ", Style::default().fg(theme::TEXT_DIM)),
+                };
+                Line::from(Span::styled(format!("{}{}", prefix, l.text), style))
+            })
+            .collect()
+    };
+
+    let block = Block::default()
+        .title(Span::styled(
+            format!(" Diff revision {} → {} ", diff.from, diff.to),
+            theme::header(),
+        ))
+        .borders(Borders::TOP)
+        .border_style(Style::default().fg(theme::BORDER));
+
+    let paragraph = Paragraph::new(lines).block(block).scroll((diff.scroll, 0));
+    f.render_widget(paragraph, area);
+}
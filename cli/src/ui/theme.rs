@@ -44,7 +44,7 @@ pub fn row_normal() -> Style {
 pub fn status_style(status: &str) -> Style {
     let color = match status {
         "Running" | "Succeeded" => GREEN,
-        "Pending" | "ContainerCreating" => YELLOW,
+        "Pending" | "ContainerCreating" | "Terminating" => YELLOW,
         "Failed" | "CrashLoopBackOff" | "Error" | "ImagePullBackOff" => RED,
         _ => TEXT,
     };